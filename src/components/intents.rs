@@ -24,6 +24,21 @@ pub struct WantsToMelee {
     pub target: Entity,
 }
 
+#[derive(Component, ConvertSaveload, Clone, Debug)]
+pub struct WantsToShoot {
+    pub target: Entity,
+}
+
+#[derive(Component, ConvertSaveload, Clone, Debug)]
+pub struct WantsToSwap {
+    pub target: Entity,
+}
+
+#[derive(Component, ConvertSaveload, Clone, Debug)]
+pub struct WantsToRecruit {
+    pub target: Entity,
+}
+
 #[derive(Component, ConvertSaveload, Clone, Debug)]
 pub struct WantsToMove {
     pub destination: Point,
@@ -33,6 +48,7 @@ pub struct WantsToMove {
 pub enum WaitCause {
     Choice,
     Confusion,
+    Sleep,
     Stun,
 }
 
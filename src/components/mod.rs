@@ -1,11 +1,15 @@
 extern crate rltk;
 extern crate specs_derive;
 
-use rltk::RGB;
+use std::fmt;
+use std::ops::Deref;
+
+use rltk::{DiceType, Point, RGB};
 use serde::{Deserialize, Serialize};
 use specs::error::NoError;
 use specs::prelude::*;
 use specs::saveload::{ConvertSaveload, Marker};
+use specs::storage::MaskedStorage;
 
 pub use intents::*;
 pub use serialization::*;
@@ -68,13 +72,100 @@ pub struct CombatStats {
     pub power: i32,
 }
 
-#[derive(Component, ConvertSaveload, Clone, Debug)]
-pub struct SuffersDamage {
+#[derive(Serialize, Deserialize, PartialEq, Copy, Clone, Debug)]
+pub enum DamageType {
+    Physical,
+    Fire,
+    Cold,
+    Poison,
+    Arcane,
+}
+
+impl fmt::Display for DamageType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            DamageType::Physical => "physical",
+            DamageType::Fire => "fire",
+            DamageType::Cold => "cold",
+            DamageType::Poison => "poison",
+            DamageType::Arcane => "arcane",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DamageInstance {
     pub amount: i32,
+    pub damage_type: DamageType,
+    pub source: Entity,
+}
+
+/// Collects every hit an entity takes during a dispatch; it is drained by the damage system
+/// before the end of the tick, so it is never saved.
+#[derive(Component, Clone, Debug)]
+pub struct SuffersDamage {
+    pub instances: Vec<DamageInstance>,
+}
+
+impl SuffersDamage {
+    pub fn new_damage(store: &mut WriteStorage<SuffersDamage>, victim: Entity, amount: i32, damage_type: DamageType, source: Entity) {
+        let instance = DamageInstance {
+            amount,
+            damage_type,
+            source,
+        };
+
+        if let Some(suffering) = store.get_mut(victim) {
+            suffering.instances.push(instance);
+        } else {
+            store
+                .insert(victim, SuffersDamage { instances: vec![instance] })
+                .expect("Unable to insert damage");
+        }
+    }
+}
+
+#[derive(Component, ConvertSaveload, Clone, Debug)]
+pub struct KilledBy {
+    pub killer: Entity,
+}
+
+#[derive(Component, ConvertSaveload, Clone, Debug)]
+pub struct DamageResistance {
+    pub damage_types: Vec<DamageType>,
+}
+
+#[derive(Component, ConvertSaveload, Clone, Debug)]
+pub struct DamageImmunity {
+    pub damage_types: Vec<DamageType>,
+}
+
+#[derive(Component, ConvertSaveload, Clone, Debug)]
+pub struct DamageVulnerability {
+    pub damage_types: Vec<DamageType>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Copy, Clone, Debug)]
+pub enum ItemKind {
+    Potion,
+    Scroll,
+}
+
+impl ItemKind {
+    pub fn use_verb(&self) -> &'static str {
+        match self {
+            ItemKind::Potion => "drinks",
+            ItemKind::Scroll => "reads",
+        }
+    }
 }
 
 #[derive(Component, Serialize, Deserialize, Clone, Debug)]
-pub struct Item;
+pub struct Item {
+    pub kind: ItemKind,
+}
 
 #[derive(Component, ConvertSaveload, Clone, Debug)]
 pub struct InBackpack {
@@ -102,6 +193,7 @@ pub struct Ranged {
 #[derive(Component, ConvertSaveload, Clone, Debug)]
 pub struct InflictsDamage {
     pub damage: i32,
+    pub damage_type: DamageType,
 }
 
 #[derive(Component, ConvertSaveload, Clone, Debug)]
@@ -122,8 +214,11 @@ pub struct TakesTurn {
     pub time_score: u32,
 }
 
+/// Turns played are counted on the global turn entity so the count is saved with the game.
 #[derive(Component, Serialize, Deserialize, Clone, Debug)]
-pub struct GlobalTurn;
+pub struct GlobalTurn {
+    pub turn: u32,
+}
 
 #[derive(Component, Serialize, Deserialize, Clone, Debug)]
 pub struct IsVisible;
@@ -133,9 +228,260 @@ pub struct CanMove {
     pub time_cost: u32,
 }
 
+#[derive(Component, Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Statistics {
+    pub kills: i32,
+    pub damage_dealt: i32,
+    pub damage_taken: i32,
+    pub items_used: i32,
+    pub steps: i32,
+}
+
+#[derive(Component, ConvertSaveload, Clone, Debug)]
+pub struct CanUseItem {
+    pub time_cost: u32,
+}
+
+#[derive(Component, ConvertSaveload, Clone, Debug)]
+pub struct CanPickUp {
+    pub time_cost: u32,
+}
+
+#[derive(Component, ConvertSaveload, Clone, Debug)]
+pub struct CanDrop {
+    pub time_cost: u32,
+}
+
+/// Overrides the user's `CanUseItem` cost for this item.
+#[derive(Component, ConvertSaveload, Clone, Debug)]
+pub struct UseTimeCost {
+    pub time_cost: u32,
+}
+
+#[derive(PartialEq, Serialize, Deserialize, Copy, Clone, Debug)]
+pub enum SpeedSource {
+    Haste,
+    Slow,
+    Terrain,
+    Encumbrance,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SpeedModifier {
+    pub source: SpeedSource,
+    pub multiplier: f32,
+    pub turns: Option<i32>,
+}
+
+/// Every modifier multiplies the time cost of the entity's actions, so a multiplier below 1 is faster.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SpeedModifiers {
+    pub modifiers: Vec<SpeedModifier>,
+}
+
+impl SpeedModifiers {
+    /// Replaces any existing modifier from the same source.
+    pub fn set(&mut self, source: SpeedSource, multiplier: f32, turns: Option<i32>) {
+        self.clear(source);
+        self.modifiers.push(SpeedModifier { source, multiplier, turns });
+    }
+
+    pub fn clear(&mut self, source: SpeedSource) {
+        self.modifiers.retain(|modifier| modifier.source != source);
+    }
+
+    pub fn get_multiplier(&self) -> f32 {
+        self.modifiers.iter().map(|modifier| modifier.multiplier).product()
+    }
+
+    pub fn get_time_cost<D: Deref<Target = MaskedStorage<SpeedModifiers>>>(store: &Storage<SpeedModifiers, D>, entity: Entity, time_cost: u32) -> u32 {
+        match store.get(entity) {
+            None => time_cost,
+            Some(speed_modifiers) => u32::max(1, (time_cost as f32 * speed_modifiers.get_multiplier()).round() as u32),
+        }
+    }
+}
+
+#[derive(Component, Serialize, Deserialize, Clone, Debug)]
+pub struct ModifiesSpeed {
+    pub source: SpeedSource,
+    pub multiplier: f32,
+    pub turns: i32,
+}
+
 #[derive(Component, ConvertSaveload, Clone, Debug)]
 pub struct CanMelee {
     pub time_cost: u32,
+    pub damage: DiceType,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LootDrop {
+    pub item: String,
+    pub weight: i32,
+}
+
+#[derive(Component, ConvertSaveload, Clone, Debug)]
+pub struct LootTable {
+    pub drop_chance: i32,
+    pub drops: Vec<LootDrop>,
+}
+
+#[derive(Component, Serialize, Deserialize, Clone, Debug)]
+pub struct Corpse;
+
+#[derive(Component, Serialize, Deserialize, Clone, Debug)]
+pub struct PartyLeader;
+
+#[derive(Component, ConvertSaveload, Clone, Debug)]
+pub struct PartyMember {
+    pub leader: Entity,
+}
+
+#[derive(PartialEq, Serialize, Deserialize, Copy, Clone, Debug)]
+pub enum AwarenessState {
+    Asleep,
+    Wandering,
+    Alert,
+    Hunting,
+}
+
+#[derive(Component, Serialize, Deserialize, Clone, Debug)]
+pub struct Awareness {
+    pub state: AwarenessState,
+}
+
+#[derive(Component, Serialize, Deserialize, Clone, Debug)]
+pub struct Sneaking;
+
+#[derive(PartialEq, Serialize, Deserialize, Copy, Clone, Debug)]
+pub enum UtilityAction {
+    Heal,
+    Flee,
+    Scatter,
+    Melee,
+    Shoot,
+    Retreat,
+    Throw,
+    PickUp,
+    Approach,
+    Search,
+    Follow,
+    Wander,
+    Wait,
+}
+
+#[derive(PartialEq, Serialize, Deserialize, Copy, Clone, Debug)]
+pub enum Consideration {
+    /// Scores 1 while health is below the given fraction of max health, otherwise 0.
+    HealthBelow(f32),
+    MoraleBroken,
+    MoraleHolding,
+    /// Scores 1 next to the target, falling off linearly to 0 at `range` tiles away.
+    TargetCloseness { range: f32 },
+    /// Scores 1 while the target is closer than the monster's preferred shooting distance, otherwise 0.
+    InsidePreferredDistance,
+    /// Scores a fresh random number between 0 and 1 every turn.
+    Random,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ActionScoring {
+    pub action: UtilityAction,
+    pub weight: f32,
+    pub considerations: Vec<Consideration>,
+}
+
+/// Lists the actions a monster will consider; an action missing from the profile is never taken.
+#[derive(Component, Serialize, Deserialize, Clone, Debug)]
+pub struct AiProfile {
+    pub actions: Vec<ActionScoring>,
+}
+
+impl AiProfile {
+    pub fn with_action(mut self, action: UtilityAction, weight: f32, considerations: &[Consideration]) -> AiProfile {
+        self.actions.retain(|scoring| scoring.action != action);
+        self.actions.push(ActionScoring { action, weight, considerations: considerations.to_vec() });
+        self
+    }
+
+    pub fn without_action(mut self, action: UtilityAction) -> AiProfile {
+        self.actions.retain(|scoring| scoring.action != action);
+        self
+    }
+
+    pub fn get_scoring(&self, action: UtilityAction) -> Option<&ActionScoring> {
+        self.actions.iter().find(|scoring| scoring.action == action)
+    }
+}
+
+#[derive(Component, ConvertSaveload, Clone, Debug)]
+pub struct Ally {
+    pub leader: Entity,
+}
+
+#[derive(Component, Serialize, Deserialize, Clone, Debug)]
+pub struct Recruitable;
+
+#[derive(Component, ConvertSaveload, Clone, Debug)]
+pub struct Scattering {
+    pub from: Point,
+    pub turns: i32,
+}
+
+#[derive(Component, ConvertSaveload, Clone, Debug)]
+pub struct PlayerMemory {
+    pub last_seen: Point,
+    pub turns_since_seen: i32,
+}
+
+#[derive(Component, ConvertSaveload, Clone, Debug)]
+pub struct Morale {
+    pub morale: i32,
+    pub max_morale: i32,
+    pub is_broken: bool,
+}
+
+impl Morale {
+    pub fn new(max_morale: i32) -> Morale {
+        Morale {
+            morale: max_morale,
+            max_morale,
+            is_broken: false,
+        }
+    }
+
+    pub fn lower(&mut self, amount: i32) {
+        self.morale = i32::max(0, self.morale - amount);
+    }
+
+    pub fn raise(&mut self, amount: i32) {
+        self.morale = i32::min(self.max_morale, self.morale + amount);
+    }
+}
+
+#[derive(Component, ConvertSaveload, Clone, Debug)]
+pub struct CanShoot {
+    pub time_cost: u32,
+    pub range: i32,
+    pub preferred_distance: i32,
+    pub damage: DiceType,
+    pub damage_type: DamageType,
+    pub ammo: Option<i32>,
+    pub projectile_name: String,
+    pub projectile_glyph: u8,
+    pub projectile_fg: RGB,
+}
+
+impl CanShoot {
+    pub fn has_ammo(&self) -> bool {
+        self.ammo.is_none_or(|ammo| ammo > 0)
+    }
+}
+
+#[derive(Component, ConvertSaveload, Clone, Debug)]
+pub struct Faction {
+    pub name: String,
 }
 
 #[derive(Component, ConvertSaveload, Clone, Debug)]
@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use specs::prelude::*;
+
+use crate::Faction;
+
+pub const PLAYER_FACTION: &str = "Player";
+pub const GOBLIN_FACTION: &str = "Goblins";
+pub const ORC_FACTION: &str = "Orcs";
+pub const OUTCAST_FACTION: &str = "Outcasts";
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Reaction {
+    Hostile,
+    Neutral,
+    Friendly,
+}
+
+pub struct FactionTable {
+    reactions: HashMap<(String, String), Reaction>,
+}
+
+impl Default for FactionTable {
+    fn default() -> Self {
+        FactionTable::new()
+    }
+}
+
+impl FactionTable {
+    pub fn new() -> FactionTable {
+        let mut faction_table = FactionTable { reactions: HashMap::new() };
+
+        faction_table.set_mutual_reaction(PLAYER_FACTION, GOBLIN_FACTION, Reaction::Hostile);
+        faction_table.set_mutual_reaction(PLAYER_FACTION, ORC_FACTION, Reaction::Hostile);
+        faction_table.set_mutual_reaction(GOBLIN_FACTION, ORC_FACTION, Reaction::Hostile);
+        faction_table.set_mutual_reaction(OUTCAST_FACTION, GOBLIN_FACTION, Reaction::Hostile);
+        faction_table.set_mutual_reaction(OUTCAST_FACTION, ORC_FACTION, Reaction::Hostile);
+
+        faction_table
+    }
+
+    pub fn set_reaction<S: ToString>(&mut self, from: S, to: S, reaction: Reaction) {
+        self.reactions.insert((from.to_string(), to.to_string()), reaction);
+    }
+
+    pub fn set_mutual_reaction<S: ToString>(&mut self, a: S, b: S, reaction: Reaction) {
+        self.set_reaction(a.to_string(), b.to_string(), reaction);
+        self.set_reaction(b.to_string(), a.to_string(), reaction);
+    }
+
+    /// Members of a faction are always friendly to each other; pairs missing from the table are neutral.
+    pub fn get_reaction(&self, from: &str, to: &str) -> Reaction {
+        if from == to {
+            return Reaction::Friendly;
+        }
+
+        *self.reactions
+            .get(&(from.to_string(), to.to_string()))
+            .unwrap_or(&Reaction::Neutral)
+    }
+
+    pub fn get_entity_reaction(&self, factions: &ReadStorage<Faction>, from: Entity, to: Entity) -> Reaction {
+        match (factions.get(from), factions.get(to)) {
+            (Some(from_faction), Some(to_faction)) => self.get_reaction(&from_faction.name, &to_faction.name),
+            _ => Reaction::Neutral,
+        }
+    }
+}
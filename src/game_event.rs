@@ -0,0 +1,51 @@
+use rltk::Point;
+use specs::prelude::*;
+
+use crate::{ActionKind, ActionResult, AttackRoll, DamageType, SpeedSource};
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum StatusEffect {
+    Confusion,
+    Speed(SpeedSource),
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum GameEvent {
+    ActionTaken { actor: Entity, kind: ActionKind, result: ActionResult, time_cost: u32 },
+    Moved { entity: Entity, from: Point, to: Point },
+    Attacked { attacker: Entity, target: Entity, roll: AttackRoll },
+    Damaged { victim: Entity, source: Entity, amount: i32, damage_type: DamageType },
+    Died { victim: Entity, killer: Entity },
+    ItemUsed { user: Entity, item: Entity },
+    StatusApplied { target: Entity, status: StatusEffect, turns: i32 },
+}
+
+/// Collects the events produced during one run of the systems.
+/// The queue is emptied at the start of the next run, so consumers scheduled after the producers see every event once.
+pub struct GameEvents {
+    events: Vec<GameEvent>,
+}
+
+impl GameEvents {
+    pub fn new() -> GameEvents {
+        GameEvents { events: Vec::new() }
+    }
+
+    pub fn push(&mut self, event: GameEvent) {
+        self.events.push(event);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &GameEvent> {
+        self.events.iter()
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+}
+
+impl Default for GameEvents {
+    fn default() -> Self {
+        GameEvents::new()
+    }
+}
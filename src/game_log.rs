@@ -1,64 +1,185 @@
 use std::cmp::{max, min};
-use std::fmt::Display;
+use std::fmt::{Display, Formatter, Result};
+
+use rltk::RGB;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum LogCategory {
+    Combat,
+    Item,
+    Status,
+    System,
+}
+
+impl Display for LogCategory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            LogCategory::Combat => write!(f, "Combat"),
+            LogCategory::Item => write!(f, "Items"),
+            LogCategory::Status => write!(f, "Status"),
+            LogCategory::System => write!(f, "System"),
+        }
+    }
+}
+
+const FILTER_CYCLE: [Option<LogCategory>; 5] = [
+    None,
+    Some(LogCategory::Combat),
+    Some(LogCategory::Item),
+    Some(LogCategory::Status),
+    Some(LogCategory::System),
+];
+
+#[derive(Clone, PartialEq)]
+pub struct LogSpan {
+    pub text: String,
+    pub color: RGB,
+}
+
+/// A log line made of colored spans.
+/// Important messages, such as damage to the player, get a highlighted background.
+#[derive(Clone, PartialEq)]
+pub struct LogMessage {
+    pub category: LogCategory,
+    pub spans: Vec<LogSpan>,
+    pub is_important: bool,
+}
+
+impl LogMessage {
+    pub fn new(category: LogCategory) -> LogMessage {
+        LogMessage {
+            category,
+            spans: Vec::new(),
+            is_important: false,
+        }
+    }
+
+    pub fn text<T: Display>(self, text: T) -> LogMessage {
+        self.colored(text, RGB::named(rltk::WHITE))
+    }
+
+    pub fn actor<T: Display>(self, name: T) -> LogMessage {
+        self.colored(name, RGB::named(rltk::RED))
+    }
+
+    pub fn item<T: Display>(self, name: T) -> LogMessage {
+        self.colored(name, RGB::named(rltk::CYAN))
+    }
+
+    pub fn amount<T: Display>(self, amount: T) -> LogMessage {
+        self.colored(amount, RGB::named(rltk::YELLOW))
+    }
+
+    pub fn colored<T: Display>(mut self, text: T, color: RGB) -> LogMessage {
+        self.spans.push(LogSpan { text: text.to_string(), color });
+        self
+    }
+
+    pub fn important(mut self) -> LogMessage {
+        self.is_important = true;
+        self
+    }
+
+    pub fn important_if(self, is_important: bool) -> LogMessage {
+        match is_important {
+            true => self.important(),
+            false => self,
+        }
+    }
+
+    pub fn get_plain_text(&self) -> String {
+        self.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+}
+
+/// Steps through showing every category, then each category on its own.
+pub fn next_log_filter(filter: Option<LogCategory>) -> Option<LogCategory> {
+    let current = FILTER_CYCLE.iter().position(|cycle_filter| *cycle_filter == filter).unwrap_or(0);
+    FILTER_CYCLE[(current + 1) % FILTER_CYCLE.len()]
+}
 
 pub struct GameLog {
     pub entries: Vec<LogEntry>,
     pub display_index: i32,
+    pub filter: Option<LogCategory>,
+    pub current_turn: u32,
 }
 
 pub struct LogEntry {
-    pub message: String,
+    pub message: LogMessage,
     pub count: usize,
+    pub turn: u32,
 }
 
 impl GameLog {
-    pub fn new_with_first_log<T: Display>(first_log: T) -> GameLog {
+    pub fn new_with_first_log(first_log: LogMessage) -> GameLog {
         GameLog {
             entries: vec![LogEntry {
-                message: first_log.to_string(),
+                message: first_log,
                 count: 1,
+                turn: 0,
             }],
             display_index: 0,
+            filter: None,
+            current_turn: 0,
         }
     }
 
-    pub fn add<T: Display>(&mut self, log: T) {
-        let new_message = log.to_string();
-
+    pub fn add(&mut self, log: LogMessage) {
         match self.entries.first_mut() {
-            None => self.add_new_entry(new_message),
+            None => self.add_new_entry(log),
             Some(last_log) => {
-                if last_log.message == new_message {
+                if last_log.message == log {
                     last_log.count += 1;
                 } else {
-                    self.add_new_entry(new_message);
+                    self.add_new_entry(log);
                 }
             }
         }
     }
 
-    fn add_new_entry(&mut self, message: String) {
+    fn add_new_entry(&mut self, message: LogMessage) {
         self.entries.insert(0, LogEntry {
             message,
             count: 1,
+            turn: self.current_turn,
         });
     }
 
+    pub fn visible_entries(&self) -> impl Iterator<Item = &LogEntry> {
+        let filter = self.filter;
+
+        self.entries
+            .iter()
+            .filter(move |entry| entry.matches(filter, ""))
+    }
+
+    pub fn cycle_filter(&mut self) {
+        self.filter = next_log_filter(self.filter);
+        self.display_index = 0;
+    }
+
     pub fn move_index(&mut self, delta: i32) {
-        self.display_index = min(
-            self.entries.len() as i32 - 1,
-            max(
-                0,
+        self.display_index = max(
+            0,
+            min(
+                self.visible_entries().count() as i32 - 1,
                 self.display_index + delta));
     }
 }
 
 impl LogEntry {
-    pub fn get_formatted_message(&self) -> String {
-        if self.count == 1 {
-            self.message.to_string()
-        } else {
-            format!("{} (x{})", self.message, self.count)
+    pub fn matches(&self, filter: Option<LogCategory>, query: &str) -> bool {
+        filter.is_none_or(|category| self.message.category == category)
+            && (query.is_empty()
+                || self.turn.to_string() == query
+                || self.message.get_plain_text().to_lowercase().contains(&query.to_lowercase()))
+    }
+
+    pub fn get_count_suffix(&self) -> Option<String> {
+        match self.count {
+            1 => None,
+            count => Some(format!(" (x{})", count)),
         }
     }
-}
\ No newline at end of file
+}
@@ -228,6 +228,10 @@ fn get_tile_glyph(idx: usize, map: &Map) -> (u8, RGB, RGB, bool) {
                 fg = RGB::from_f32(0.5, 1.0, 0.5);
                 glyph = rltk::to_cp437('·')
             }
+            TileType::Mud => {
+                fg = RGB::named(rltk::SADDLEBROWN);
+                glyph = rltk::to_cp437('≈')
+            }
             TileType::Wall => {
                 fg = RGB::from_f32(0.0, 1.0, 0.0);
                 glyph = rltk::to_cp437(get_wall_glyph(&map, pt.x, pt.y));
@@ -0,0 +1,180 @@
+extern crate rltk;
+
+use specs::prelude::*;
+
+use crate::{CanDrop, CanMelee, CanMove, CanPickUp, CanUseItem, CombatStats, CONSOLE_INDEX, Confusion, Context, GlobalTurnTimeScore, InBackpack, InputAction, Keymap, Name, SNEAK_TIME_MULTIPLIER, Sneaking, SpeedModifiers, State, Statistics};
+
+use self::rltk::{ColorPair, DiceType, Point, Rect, RGB};
+
+const SHEET_WIDTH: i32 = 50;
+const SHEET_HEIGHT: i32 = 40;
+const TEXT_OFFSET: i32 = 2;
+const VALUE_COLUMN: i32 = 22;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum CharacterSheetResult { Close, NoResponse }
+
+pub fn show_character_sheet(state: &mut State, context: &mut Context) -> CharacterSheetResult {
+    CharacterSheetDrawer { state, context }.show_character_sheet()
+}
+
+struct CharacterSheetDrawer<'a, 'b> {
+    state: &'a mut State,
+    context: &'a mut Context<'b>,
+}
+
+fn format_dice(dice: DiceType) -> String {
+    match dice.bonus {
+        0 => format!("{}d{}", dice.n_dice, dice.die_type),
+        bonus if bonus > 0 => format!("{}d{}+{}", dice.n_dice, dice.die_type, bonus),
+        bonus => format!("{}d{}{}", dice.n_dice, dice.die_type, bonus),
+    }
+}
+
+impl<'a, 'b> CharacterSheetDrawer<'a, 'b> {
+    pub fn show_character_sheet(&mut self) -> CharacterSheetResult {
+        self.context.set_target(CONSOLE_INDEX.ui);
+
+        self.draw_character_sheet();
+
+        self.context.set_target(CONSOLE_INDEX.base);
+
+        let keymap = self.state.ecs.fetch::<Keymap>();
+
+        match self.context.rltk.key {
+            Some(key) if keymap.is_bound(InputAction::Cancel, key) || keymap.is_bound(InputAction::CharacterSheet, key) => CharacterSheetResult::Close,
+            _ => CharacterSheetResult::NoResponse,
+        }
+    }
+
+    fn draw_character_sheet(&mut self) {
+        let lines = self.get_sheet_lines();
+        let keymap = self.state.ecs.fetch::<Keymap>();
+
+        let (window_width, window_height) = self.context.get_screen_size();
+        let left_x = (window_width as i32 - SHEET_WIDTH) / 2;
+        let top_y = (window_height as i32 - SHEET_HEIGHT) / 2;
+
+        let bg = RGB::named(rltk::BLACK);
+        let highlight_fg = RGB::named(rltk::YELLOW);
+        let plain_fg = RGB::named(rltk::WHITE);
+        let dim_fg = RGB::named(rltk::GRAY);
+
+        self.context.draw_box(
+            Rect::with_size(left_x, top_y, SHEET_WIDTH, SHEET_HEIGHT),
+            ColorPair::new(plain_fg, bg));
+
+        self.context.print_color(
+            Point::new(left_x + 3, top_y),
+            " Character ",
+            ColorPair::new(highlight_fg, bg));
+
+        let mut y = top_y + 2;
+        for (label, value) in lines.iter() {
+            match value {
+                None => {
+                    y += 1;
+                    self.context.print_color(Point::new(left_x + TEXT_OFFSET, y), label, ColorPair::new(highlight_fg, bg));
+                }
+                Some(value) => {
+                    self.context.print_color(Point::new(left_x + TEXT_OFFSET, y), label, ColorPair::new(dim_fg, bg));
+                    self.context.print_color(Point::new(left_x + VALUE_COLUMN, y), value, ColorPair::new(plain_fg, bg));
+                }
+            }
+            y += 1;
+        }
+
+        self.context.print_color(
+            Point::new(left_x + 3, top_y + SHEET_HEIGHT),
+            format!(" {} to close ", keymap.describe(InputAction::Cancel)),
+            ColorPair::new(highlight_fg, bg));
+    }
+
+    /// Section headers have no value; every other line is a label and its value.
+    fn get_sheet_lines(&self) -> Vec<(String, Option<String>)> {
+        let ecs = &self.state.ecs;
+        let player_entity = *ecs.fetch::<Entity>();
+        let names = ecs.read_storage::<Name>();
+        let combat_stats = ecs.read_storage::<CombatStats>();
+        let can_melee = ecs.read_storage::<CanMelee>();
+        let in_backpacks = ecs.read_storage::<InBackpack>();
+        let confusions = ecs.read_storage::<Confusion>();
+        let sneaking = ecs.read_storage::<Sneaking>();
+        let speed_modifiers = ecs.read_storage::<SpeedModifiers>();
+        let statistics = ecs.read_storage::<Statistics>();
+        let global_turn_time_score = ecs.fetch::<GlobalTurnTimeScore>();
+        let is_sneaking = sneaking.get(player_entity).is_some();
+
+        let mut lines = Vec::new();
+        let mut line = |label: &str, value: Option<String>| lines.push((label.to_string(), value));
+
+        line(&names.get(player_entity).map_or("Player".to_string(), |name| name.name.clone()), None);
+        if let Some(stats) = combat_stats.get(player_entity) {
+            line("Health", Some(format!("{} / {}", stats.hp, stats.max_hp)));
+            line("Power", Some(stats.power.to_string()));
+            line("Defense", Some(stats.defense.to_string()));
+        }
+        if let Some(can_melee) = can_melee.get(player_entity) {
+            line("Melee damage", Some(format_dice(can_melee.damage)));
+        }
+
+        line("Pack", None);
+        let carried = in_backpacks.join().filter(|in_backpack| in_backpack.owner == player_entity).count();
+        line("Carrying", Some(format!("{} items", carried)));
+
+        line("Status", None);
+        let mut statuses = Vec::new();
+        if let Some(confusion) = confusions.get(player_entity) {
+            statuses.push(("Confused".to_string(), format!("{} turns", confusion.turns)));
+        }
+        if is_sneaking {
+            statuses.push(("Sneaking".to_string(), "until toggled".to_string()));
+        }
+        if let Some(speed_modifiers) = speed_modifiers.get(player_entity) {
+            for modifier in speed_modifiers.modifiers.iter() {
+                let duration = modifier.turns.map_or("while it lasts".to_string(), |turns| format!("{} turns", turns));
+                statuses.push((format!("{:?}", modifier.source), format!("x{:.2} time, {}", modifier.multiplier, duration)));
+            }
+        }
+        if statuses.is_empty() {
+            line("None", Some(String::new()));
+        }
+        for (label, value) in statuses {
+            line(&label, Some(value));
+        }
+
+        line("Action costs", None);
+        let costs = [
+            ("Move", ecs.read_storage::<CanMove>().get(player_entity).map(|can_move| match is_sneaking {
+                true => can_move.time_cost * SNEAK_TIME_MULTIPLIER,
+                false => can_move.time_cost,
+            })),
+            ("Melee", can_melee.get(player_entity).map(|can_melee| can_melee.time_cost)),
+            ("Use item", ecs.read_storage::<CanUseItem>().get(player_entity).map(|can_use_item| can_use_item.time_cost)),
+            ("Pick up", ecs.read_storage::<CanPickUp>().get(player_entity).map(|can_pick_up| can_pick_up.time_cost)),
+            ("Drop", ecs.read_storage::<CanDrop>().get(player_entity).map(|can_drop| can_drop.time_cost)),
+        ];
+        for (label, base_cost) in costs.iter() {
+            if let Some(base_cost) = base_cost {
+                let cost = SpeedModifiers::get_time_cost(&speed_modifiers, player_entity, *base_cost);
+                let value = match cost == *base_cost {
+                    true => cost.to_string(),
+                    false => format!("{} (base {})", cost, base_cost),
+                };
+                line(label, Some(value));
+            }
+        }
+
+        line("Record", None);
+        line("Turns played", Some(global_turn_time_score.turn.to_string()));
+        if let Some(statistics) = statistics.get(player_entity) {
+            line("Kills", Some(statistics.kills.to_string()));
+            line("Damage dealt", Some(statistics.damage_dealt.to_string()));
+            line("Damage taken", Some(statistics.damage_taken.to_string()));
+            line("Items used", Some(statistics.items_used.to_string()));
+            line("Steps taken", Some(statistics.steps.to_string()));
+        }
+
+        lines
+    }
+}
@@ -0,0 +1,182 @@
+extern crate rltk;
+
+use crate::{CONSOLE_INDEX, Context, InputAction, Keymap, State};
+
+use self::rltk::{ColorPair, Point, Rect, RGB};
+
+const HELP_WIDTH: i32 = 74;
+const HELP_HEIGHT: i32 = 40;
+const COLUMN_OFFSET: i32 = 2;
+const LEGEND_COLUMN_OFFSET: i32 = 40;
+const KEY_COLUMN_WIDTH: i32 = 20;
+const MODE_TEXT_OFFSET: i32 = 11;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum HelpResult { Close, NoResponse }
+
+pub fn show_help(state: &mut State, context: &mut Context) -> HelpResult {
+    HelpDrawer { state, context }.show_help()
+}
+
+struct HelpDrawer<'a, 'b> {
+    state: &'a mut State,
+    context: &'a mut Context<'b>,
+}
+
+/// Each glyph once, without its colour, since most glyphs are drawn in several colours.
+const GLYPH_LEGEND: [(char, &str); 22] = [
+    ('@', "You"),
+    ('g', "Goblin, archer, shaman or outcast"),
+    ('G', "Goblin chief"),
+    ('o', "Orc"),
+    ('¡', "Potion, drink it"),
+    (')', "Scroll, read it"),
+    ('%', "Corpse"),
+    ('·', "Floor"),
+    ('≈', "Mud, slows movement"),
+    ('∙', "Arrow in flight"),
+    ('*', "Firebolt in flight"),
+    ('░', "Blast"),
+    ('!', "Wakes, hunts or flees"),
+    ('‼', "Takes damage"),
+    ('○', "Attack misses"),
+    ('¿', "Attack fumbled"),
+    ('♥', "Healed or recruited"),
+    ('?', "Confused"),
+    ('♪', "Waits"),
+    ('z', "Sleeps"),
+    ('»', "Sped up"),
+    ('«', "Slowed down"),
+];
+
+/// Breaks text into lines of at most `width` characters, splitting at spaces where it can.
+fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        let mut word: String = word.to_string();
+
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+
+        while word.chars().count() > width {
+            let rest = word.split_off(word.char_indices().nth(width).map_or(word.len(), |(index, _)| index));
+            lines.push(word);
+            word = rest;
+        }
+
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&word);
+    }
+
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+impl<'a, 'b> HelpDrawer<'a, 'b> {
+    pub fn show_help(&mut self) -> HelpResult {
+        self.context.set_target(CONSOLE_INDEX.ui);
+
+        self.draw_help();
+
+        self.context.set_target(CONSOLE_INDEX.base);
+
+        let keymap = self.state.ecs.fetch::<Keymap>();
+
+        match self.context.rltk.key {
+            Some(key) if keymap.is_bound(InputAction::Cancel, key) || keymap.is_bound(InputAction::ShowHelp, key) => HelpResult::Close,
+            _ => HelpResult::NoResponse,
+        }
+    }
+
+    fn draw_help(&mut self) {
+        let keymap = self.state.ecs.fetch::<Keymap>();
+
+        let (window_width, window_height) = self.context.get_screen_size();
+        let left_x = (window_width as i32 - HELP_WIDTH) / 2;
+        let top_y = (window_height as i32 - HELP_HEIGHT) / 2;
+
+        let bg = RGB::named(rltk::BLACK);
+        let highlight_fg = RGB::named(rltk::YELLOW);
+        let plain_fg = RGB::named(rltk::WHITE);
+        let dim_fg = RGB::named(rltk::GRAY);
+
+        self.context.draw_box(
+            Rect::with_size(left_x, top_y, HELP_WIDTH, HELP_HEIGHT),
+            ColorPair::new(plain_fg, bg));
+
+        self.context.print_color(
+            Point::new(left_x + 3, top_y),
+            " Help ",
+            ColorPair::new(highlight_fg, bg));
+
+        let keys_x = left_x + COLUMN_OFFSET;
+        let mut y = top_y + 2;
+
+        self.context.print_color(Point::new(keys_x, y), "Controls", ColorPair::new(highlight_fg, bg));
+        y += 1;
+
+        for action in InputAction::ALL.iter() {
+            self.context.print_color(Point::new(keys_x, y), action.get_label(), ColorPair::new(plain_fg, bg));
+            self.context.print_color(
+                Point::new(keys_x + KEY_COLUMN_WIDTH, y),
+                keymap.describe(*action),
+                ColorPair::new(dim_fg, bg));
+            y += 1;
+        }
+
+        let legend_x = left_x + LEGEND_COLUMN_OFFSET;
+        let mut legend_y = top_y + 2;
+
+        self.context.print_color(Point::new(legend_x, legend_y), "Legend", ColorPair::new(highlight_fg, bg));
+        legend_y += 1;
+
+        for (glyph, description) in GLYPH_LEGEND.iter() {
+            self.context.set(Point::new(legend_x, legend_y), ColorPair::new(plain_fg, bg), rltk::to_cp437(*glyph));
+            self.context.print_color(Point::new(legend_x + 2, legend_y), description, ColorPair::new(plain_fg, bg));
+            legend_y += 1;
+        }
+
+        y = i32::max(y, legend_y) + 1;
+
+        let cancel_keys = keymap.describe(InputAction::Cancel);
+        let modes = [
+            ("Inventory", format!("{} lists your items. Press a letter or {} to use one. {} drops an item the same way. {} closes either menu.",
+                keymap.describe(InputAction::Inventory),
+                keymap.describe(InputAction::Confirm),
+                keymap.describe(InputAction::Drop),
+                cancel_keys)),
+            ("Targeting", format!("Scrolls with a range show the tiles you can reach in blue. {} cycles hostiles, move keys or the mouse move the cursor and {} or a click fires. Orange marks the blast area and a red line of fire means a wall is in the way.",
+                keymap.describe(InputAction::NextTarget),
+                keymap.describe(InputAction::Confirm))),
+            ("Combat", "Move into an enemy to attack, into an ally to swap places and into an outcast to recruit it. Sneaking is quieter but slower.".to_string()),
+        ];
+
+        let text_x = keys_x + MODE_TEXT_OFFSET;
+        let text_width = (left_x + HELP_WIDTH - text_x) as usize - 1;
+
+        for (title, text) in modes.iter() {
+            self.context.print_color(Point::new(keys_x, y), title, ColorPair::new(highlight_fg, bg));
+
+            for line in wrap_text(text, text_width) {
+                if y >= top_y + HELP_HEIGHT {
+                    break;
+                }
+                self.context.print_color(Point::new(text_x, y), line, ColorPair::new(plain_fg, bg));
+                y += 1;
+            }
+        }
+
+        self.context.print_color(
+            Point::new(left_x + 3, top_y + HELP_HEIGHT),
+            format!(" {} to close ", cancel_keys),
+            ColorPair::new(highlight_fg, bg));
+    }
+}
@@ -0,0 +1,222 @@
+extern crate rltk;
+
+use specs::prelude::*;
+
+use crate::{CONSOLE_INDEX, Context, GameLog, InputAction, Keymap, LogCategory, LogEntry, next_log_filter, State};
+
+use self::rltk::{ColorPair, Point, Rect, RGB, VirtualKeyCode};
+
+const HEADER_HEIGHT: i32 = 3;
+const FOOTER_HEIGHT: i32 = 2;
+const TURN_COLUMN_WIDTH: i32 = 7;
+const TEXT_OFFSET: i32 = 2;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum HistoryResult { Close, NoResponse }
+
+/// Paging, filter and search state of the message history screen.
+/// Kept as a resource because the search query does not fit in a `Copy` run state.
+#[derive(Default)]
+pub struct HistoryViewer {
+    pub offset: usize,
+    pub filter: Option<LogCategory>,
+    pub query: String,
+    pub is_searching: bool,
+}
+
+impl HistoryViewer {
+    pub fn reset(&mut self) {
+        *self = HistoryViewer::default();
+    }
+}
+
+pub fn show_history(state: &mut State, context: &mut Context) -> HistoryResult {
+    HistoryDrawer { state, context }.show_history()
+}
+
+struct HistoryDrawer<'a, 'b> {
+    state: &'a mut State,
+    context: &'a mut Context<'b>,
+}
+
+impl<'a, 'b> HistoryDrawer<'a, 'b> {
+    pub fn show_history(&mut self) -> HistoryResult {
+        self.context.set_target(CONSOLE_INDEX.ui);
+
+        let (window_width, window_height) = self.context.get_screen_size();
+        let (window_width, window_height) = (window_width as i32, window_height as i32);
+        let page_size = (window_height - HEADER_HEIGHT - FOOTER_HEIGHT - 1).max(1) as usize;
+
+        self.draw_history(window_width, window_height, page_size);
+
+        self.context.set_target(CONSOLE_INDEX.base);
+
+        match self.context.rltk.key {
+            None => HistoryResult::NoResponse,
+            Some(key) => self.handle_key(key, page_size),
+        }
+    }
+
+    fn draw_history(&mut self, window_width: i32, window_height: i32, page_size: usize) {
+        let game_log = self.state.ecs.fetch::<GameLog>();
+        let keymap = self.state.ecs.fetch::<Keymap>();
+        let mut viewer = self.state.ecs.write_resource::<HistoryViewer>();
+
+        let bg = RGB::named(rltk::BLACK);
+        let highlight_fg = RGB::named(rltk::YELLOW);
+        let plain_fg = RGB::named(rltk::WHITE);
+        let dim_fg = RGB::named(rltk::GRAY);
+
+        self.context.draw_box(
+            Rect::with_size(0, 0, window_width - 1, window_height - 1),
+            ColorPair::new(plain_fg, bg));
+
+        self.context.print_color(
+            Point::new(TEXT_OFFSET + 1, 0),
+            " Message history ",
+            ColorPair::new(highlight_fg, bg));
+
+        let filter_name = viewer.filter.map_or("All".to_string(), |category| category.to_string());
+        let cursor = if viewer.is_searching { "_" } else { "" };
+        self.context.print_color(
+            Point::new(TEXT_OFFSET, 1),
+            format!("Filter: {:<8} Search: {}{}", filter_name, viewer.query, cursor),
+            ColorPair::new(plain_fg, bg));
+
+        let entries: Vec<&LogEntry> = game_log
+            .entries
+            .iter()
+            .filter(|entry| entry.matches(viewer.filter, &viewer.query))
+            .collect();
+
+        let max_offset = entries.len().saturating_sub(page_size);
+        viewer.offset = viewer.offset.min(max_offset);
+
+        if entries.is_empty() {
+            self.context.print_color(
+                Point::new(TEXT_OFFSET, HEADER_HEIGHT),
+                "No matching messages.",
+                ColorPair::new(dim_fg, bg));
+        }
+
+        let mut y = HEADER_HEIGHT + page_size as i32 - 1;
+        for entry in entries.iter().skip(viewer.offset).take(page_size) {
+            self.context.print_color(
+                Point::new(TEXT_OFFSET, y),
+                format!("{:>5}", entry.turn),
+                ColorPair::new(dim_fg, bg));
+
+            let mut x = TEXT_OFFSET + TURN_COLUMN_WIDTH;
+            let entry_bg = match entry.message.is_important {
+                true => RGB::named(rltk::DARK_RED),
+                false => bg,
+            };
+
+            for span in entry.message.spans.iter() {
+                self.context.print_color(Point::new(x, y), &span.text, ColorPair::new(span.color, entry_bg));
+                x += span.text.chars().count() as i32;
+            }
+
+            if let Some(count_suffix) = entry.get_count_suffix() {
+                self.context.print_color(Point::new(x, y), count_suffix, ColorPair::new(dim_fg, entry_bg));
+            }
+
+            y -= 1;
+        }
+
+        self.context.print_color(
+            Point::new(TEXT_OFFSET, window_height - FOOTER_HEIGHT),
+            format!(
+                "{}-{} of {}   PgUp/PgDn page  Up/Down scroll  Tab filter  / search  {} to close",
+                (viewer.offset + 1).min(entries.len()),
+                (viewer.offset + page_size).min(entries.len()),
+                entries.len(),
+                keymap.describe(InputAction::Cancel)),
+            ColorPair::new(highlight_fg, bg));
+    }
+
+    fn handle_key(&mut self, key: VirtualKeyCode, page_size: usize) -> HistoryResult {
+        let keymap = self.state.ecs.fetch::<Keymap>();
+        let mut viewer = self.state.ecs.write_resource::<HistoryViewer>();
+
+        if viewer.is_searching {
+            match key {
+                VirtualKeyCode::Return |
+                VirtualKeyCode::Escape => viewer.is_searching = false,
+                VirtualKeyCode::Back => { viewer.query.pop(); }
+                _ => {
+                    if let Some(character) = get_search_char(key) {
+                        viewer.query.push(character);
+                    }
+                }
+            }
+
+            viewer.offset = 0;
+            return HistoryResult::NoResponse;
+        }
+
+        if keymap.is_bound(InputAction::Cancel, key) || keymap.is_bound(InputAction::ShowHistory, key) {
+            return HistoryResult::Close;
+        }
+
+        match key {
+            VirtualKeyCode::PageUp => viewer.offset = viewer.offset.saturating_add(page_size),
+            VirtualKeyCode::PageDown => viewer.offset = viewer.offset.saturating_sub(page_size),
+            VirtualKeyCode::Up => viewer.offset = viewer.offset.saturating_add(1),
+            VirtualKeyCode::Down => viewer.offset = viewer.offset.saturating_sub(1),
+            VirtualKeyCode::Home => viewer.offset = usize::MAX,
+            VirtualKeyCode::End => viewer.offset = 0,
+            VirtualKeyCode::Tab => {
+                viewer.filter = next_log_filter(viewer.filter);
+                viewer.offset = 0;
+            }
+            VirtualKeyCode::Slash => {
+                viewer.query.clear();
+                viewer.is_searching = true;
+                viewer.offset = 0;
+            }
+            VirtualKeyCode::Back => {
+                viewer.query.clear();
+                viewer.offset = 0;
+            }
+            _ => {}
+        }
+
+        HistoryResult::NoResponse
+    }
+}
+
+/// The character a key types into the search query, if any.
+fn get_search_char(key: VirtualKeyCode) -> Option<char> {
+    let letter = rltk::letter_to_option(key);
+    if letter >= 0 {
+        return Some((b'a' + letter as u8) as char);
+    }
+
+    let digit = match key {
+        VirtualKeyCode::Key0 | VirtualKeyCode::Numpad0 => Some(0),
+        VirtualKeyCode::Key1 | VirtualKeyCode::Numpad1 => Some(1),
+        VirtualKeyCode::Key2 | VirtualKeyCode::Numpad2 => Some(2),
+        VirtualKeyCode::Key3 | VirtualKeyCode::Numpad3 => Some(3),
+        VirtualKeyCode::Key4 | VirtualKeyCode::Numpad4 => Some(4),
+        VirtualKeyCode::Key5 | VirtualKeyCode::Numpad5 => Some(5),
+        VirtualKeyCode::Key6 | VirtualKeyCode::Numpad6 => Some(6),
+        VirtualKeyCode::Key7 | VirtualKeyCode::Numpad7 => Some(7),
+        VirtualKeyCode::Key8 | VirtualKeyCode::Numpad8 => Some(8),
+        VirtualKeyCode::Key9 | VirtualKeyCode::Numpad9 => Some(9),
+        _ => None,
+    };
+    if let Some(digit) = digit {
+        return Some((b'0' + digit) as char);
+    }
+
+    match key {
+        VirtualKeyCode::Space => Some(' '),
+        VirtualKeyCode::Period => Some('.'),
+        VirtualKeyCode::Comma => Some(','),
+        VirtualKeyCode::Minus => Some('-'),
+        VirtualKeyCode::Apostrophe => Some('\''),
+        VirtualKeyCode::Slash => Some('/'),
+        _ => None,
+    }
+}
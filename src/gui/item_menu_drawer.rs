@@ -2,13 +2,31 @@ extern crate rltk;
 
 use specs::prelude::*;
 
-use crate::{CONSOLE_INDEX, Context, InBackpack, Name, State};
+use crate::{AreaOfEffect, CanUseItem, Confusion, CONSOLE_INDEX, Consumable, Context, InBackpack, InflictsDamage, InputAction, Item, ItemKind, Keymap, ModifiesSpeed, Name, ProvidesHealing, Ranged, State, UseTimeCost};
 
 use self::rltk::{ColorPair, Point, Rect, RGB, VirtualKeyCode};
 
+const ITEMS_PER_PAGE: usize = 20;
+const INVENTORY_WIDTH: i32 = 31;
+const DETAIL_WIDTH: i32 = 36;
+const BORDER_TEXT_OFFSET: i32 = 3;
+
 #[derive(PartialEq, Copy, Clone)]
 pub enum ItemMenuResult { Cancel, NoResponse, Selected(Entity) }
 
+/// Page and highlighted line of the item menu, kept between frames.
+#[derive(Default)]
+pub struct ItemMenuState {
+    pub page: usize,
+    pub cursor: usize,
+}
+
+impl ItemMenuState {
+    pub fn reset(&mut self) {
+        *self = ItemMenuState::default();
+    }
+}
+
 pub fn show_inventory(state: &mut State, context: &mut Context) -> ItemMenuResult {
     ItemMenuDrawer {
         state,
@@ -39,96 +57,273 @@ struct ItemMenuDrawerSettings<'a> {
     pub title: &'a str,
 }
 
+/// Identical items, grouped by name, shown on a single line.
+struct ItemStack {
+    name: String,
+    kind: Option<ItemKind>,
+    entities: Vec<Entity>,
+}
+
 impl<'a, 'b> ItemMenuDrawer<'a, 'b> {
     pub fn show_item_selection_menu(&mut self) -> ItemMenuResult {
         self.context.set_target(CONSOLE_INDEX.ui);
 
+        let stacks = self.get_item_stacks();
+        let page_count = usize::max(1, stacks.len().div_ceil(ITEMS_PER_PAGE));
+
+        {
+            let mut menu_state = self.state.ecs.write_resource::<ItemMenuState>();
+            menu_state.page = menu_state.page.min(page_count - 1);
+            let page_len = stacks.len().saturating_sub(menu_state.page * ITEMS_PER_PAGE).min(ITEMS_PER_PAGE);
+            menu_state.cursor = menu_state.cursor.min(page_len.saturating_sub(1));
+        }
+
+        let (page, cursor) = {
+            let menu_state = self.state.ecs.fetch::<ItemMenuState>();
+            (menu_state.page, menu_state.cursor)
+        };
+        let page_stacks: Vec<&ItemStack> = stacks.iter().skip(page * ITEMS_PER_PAGE).take(ITEMS_PER_PAGE).collect();
+
+        self.draw_item_list(&page_stacks, cursor, page, page_count);
+
+        if let Some(stack) = page_stacks.get(cursor) {
+            self.draw_item_details(stack);
+        }
+
+        self.context.set_target(CONSOLE_INDEX.base);
+
+        match self.context.rltk.key {
+            None => ItemMenuResult::NoResponse,
+            Some(key) => self.handle_key(key, &page_stacks, page_count),
+        }
+    }
+
+    fn get_item_stacks(&self) -> Vec<ItemStack> {
         let player_entity = self.state.ecs.fetch::<Entity>();
         let names = self.state.ecs.read_storage::<Name>();
+        let items = self.state.ecs.read_storage::<Item>();
         let in_backpacks = self.state.ecs.read_storage::<InBackpack>();
         let entities = self.state.ecs.entities();
 
-        let inventory_count = in_backpacks
-            .join()
-            .filter(|in_backpack| {
-                in_backpack.owner == *player_entity
-            })
-            .count();
+        let mut stacks: Vec<ItemStack> = Vec::new();
+
+        for (name, in_backpack, entity) in (&names, &in_backpacks, &entities).join() {
+            if in_backpack.owner != *player_entity {
+                continue;
+            }
+
+            match stacks.iter_mut().find(|stack| stack.name == name.name) {
+                Some(stack) => stack.entities.push(entity),
+                None => stacks.push(ItemStack {
+                    name: name.name.clone(),
+                    kind: items.get(entity).map(|item| item.kind),
+                    entities: vec![entity],
+                }),
+            }
+        }
+
+        stacks.sort_by(|a, b| {
+            let kind_order = |stack: &ItemStack| stack.kind.map_or(usize::MAX, |kind| kind as usize);
+            kind_order(a).cmp(&kind_order(b)).then_with(|| a.name.cmp(&b.name))
+        });
+
+        stacks
+    }
+
+    fn draw_item_list(&mut self, page_stacks: &[&ItemStack], cursor: usize, page: usize, page_count: usize) {
+        let keymap = self.state.ecs.fetch::<Keymap>();
 
         let (window_width, window_height) = self.context.get_screen_size();
 
-        let mut y = window_height as i32 / 2 - (inventory_count / 2) as i32;
         let bg = RGB::named(rltk::BLACK);
         let highlight_fg = RGB::named(rltk::YELLOW);
         let plain_fg = RGB::named(rltk::WHITE);
+        let cursor_bg = RGB::named(rltk::DARKSLATEGRAY);
 
-        const INVENTORY_WIDTH: i32 = 31;
-        const BORDER_TEXT_OFFSET: i32 = 3;
-
-
-        let inventory_x: i32 = window_width as i32 / 2 - (INVENTORY_WIDTH / 2);
+        let line_count = usize::max(1, page_stacks.len()) as i32;
+        let inventory_x = window_width as i32 / 2 - (INVENTORY_WIDTH + DETAIL_WIDTH) / 2;
+        let top_y = window_height as i32 / 2 - line_count / 2;
 
         self.context.draw_box(
-            Rect::with_size(inventory_x, y - 2, INVENTORY_WIDTH, (inventory_count + 3) as i32),
+            Rect::with_size(inventory_x, top_y - 2, INVENTORY_WIDTH, line_count + 5),
             ColorPair::new(plain_fg, bg));
 
         self.context.print_color(
-            Point::new(
-                inventory_x + BORDER_TEXT_OFFSET,
-                y - 2),
+            Point::new(inventory_x + BORDER_TEXT_OFFSET, top_y - 2),
             &self.settings.title,
-            ColorPair::new(
-                highlight_fg,
-                bg));
+            ColorPair::new(highlight_fg, bg));
+
+        if page_count > 1 {
+            self.context.print_color(
+                Point::new(inventory_x + INVENTORY_WIDTH - 9, top_y - 2),
+                format!(" {}/{} ", page + 1, page_count),
+                ColorPair::new(highlight_fg, bg));
+        }
+
+        if page_stacks.is_empty() {
+            self.context.print_color(Point::new(inventory_x + 2, top_y), "Your pack is empty.", ColorPair::new(plain_fg, bg));
+        }
+
+        for (i, stack) in page_stacks.iter().enumerate() {
+            let y = top_y + i as i32;
+            let line_bg = match i == cursor {
+                true => cursor_bg,
+                false => bg,
+            };
+            let hotkey = (b'a' + i as u8) as char;
+            let label = match stack.entities.len() {
+                1 => stack.name.clone(),
+                count => format!("{} (x{})", stack.name, count),
+            };
+
+            self.context.print_color(Point::new(inventory_x + 2, y), "(", ColorPair::new(plain_fg, line_bg));
+            self.context.print_color(Point::new(inventory_x + 3, y), hotkey, ColorPair::new(highlight_fg, line_bg));
+            self.context.print_color(Point::new(inventory_x + 4, y), ")", ColorPair::new(plain_fg, line_bg));
+            self.context.print_color(
+                Point::new(inventory_x + 6, y),
+                format!("{:<width$}", label, width = (INVENTORY_WIDTH - 7) as usize),
+                ColorPair::new(plain_fg, line_bg));
+        }
 
         self.context.print_color(
-            Point::new(
-                inventory_x + BORDER_TEXT_OFFSET,
-                y + inventory_count as i32 + 1),
-            "ESCAPE to cancel",
-            ColorPair::new(
-                highlight_fg,
-                bg));
-
-        let inventory = (&names, &in_backpacks, &entities)
-            .join()
-            .filter(|(_, in_backpack, _)| {
-                in_backpack.owner == *player_entity
-            });
-
-        let mut hotkey = 'a' as u8;
-        let mut selectable_items: Vec<Entity> = Vec::new();
-
-        for (name, _in_backpack, entity) in inventory {
-            self.context.set(Point::new(inventory_x + 2, y), ColorPair::new(plain_fg, bg), rltk::to_cp437('('));
-            self.context.set(Point::new(inventory_x + 3, y), ColorPair::new(highlight_fg, bg), hotkey);
-            self.context.set(Point::new(inventory_x + 4, y), ColorPair::new(plain_fg, bg), rltk::to_cp437(')'));
-
-            self.context.print_color(Point::new(inventory_x + 6, y), &name.name, ColorPair::new(plain_fg, bg));
-
-            selectable_items.push(entity);
-            y += 1;
-            hotkey += 1;
+            Point::new(inventory_x + BORDER_TEXT_OFFSET, top_y + line_count + 1),
+            format!("{}/{} to pick, {} to choose",
+                keymap.describe(InputAction::MenuUp),
+                keymap.describe(InputAction::MenuDown),
+                keymap.describe(InputAction::Confirm)),
+            ColorPair::new(plain_fg, bg));
+
+        if page_count > 1 {
+            self.context.print_color(
+                Point::new(inventory_x + BORDER_TEXT_OFFSET, top_y + line_count + 2),
+                format!("{}/{} to turn pages",
+                    keymap.describe(InputAction::PreviousPage),
+                    keymap.describe(InputAction::NextPage)),
+                ColorPair::new(plain_fg, bg));
         }
-        self.context.set_target(CONSOLE_INDEX.base);
 
-        match self.context.rltk.key {
-            None => ItemMenuResult::NoResponse,
-            Some(key) => {
-                match key {
-                    VirtualKeyCode::Escape => ItemMenuResult::Cancel,
-                    _ => {
-                        let selection = rltk::letter_to_option(key) as usize;
-
-                        let selected_item_or_none = selectable_items.get(selection);
-
-                        match selected_item_or_none {
-                            None => ItemMenuResult::NoResponse,
-                            Some(selected_item) => ItemMenuResult::Selected(*selected_item),
-                        }
-                    }
+        self.context.print_color(
+            Point::new(inventory_x + BORDER_TEXT_OFFSET, top_y + line_count + 3),
+            format!("{} to cancel", keymap.describe(InputAction::Cancel)),
+            ColorPair::new(highlight_fg, bg));
+    }
+
+    fn draw_item_details(&mut self, stack: &ItemStack) {
+        let lines = self.get_item_details(stack);
+
+        let (window_width, window_height) = self.context.get_screen_size();
+
+        let bg = RGB::named(rltk::BLACK);
+        let highlight_fg = RGB::named(rltk::YELLOW);
+        let plain_fg = RGB::named(rltk::WHITE);
+
+        let detail_x = window_width as i32 / 2 - (INVENTORY_WIDTH + DETAIL_WIDTH) / 2 + INVENTORY_WIDTH + 1;
+        let top_y = window_height as i32 / 2 - lines.len() as i32 / 2;
+
+        self.context.draw_box(
+            Rect::with_size(detail_x, top_y - 2, DETAIL_WIDTH, lines.len() as i32 + 3),
+            ColorPair::new(plain_fg, bg));
+
+        self.context.print_color(
+            Point::new(detail_x + BORDER_TEXT_OFFSET, top_y - 2),
+            &stack.name,
+            ColorPair::new(highlight_fg, bg));
+
+        let max_line_length = (DETAIL_WIDTH - 3) as usize;
+        for (i, line) in lines.iter().enumerate() {
+            let line: String = line.chars().take(max_line_length).collect();
+            self.context.print_color(Point::new(detail_x + 2, top_y + i as i32), line, ColorPair::new(plain_fg, bg));
+        }
+    }
+
+    fn get_item_details(&self, stack: &ItemStack) -> Vec<String> {
+        let ecs = &self.state.ecs;
+        let player_entity = ecs.fetch::<Entity>();
+        let item = stack.entities[0];
+
+        let mut lines = Vec::new();
+
+        match stack.kind {
+            Some(ItemKind::Potion) => lines.push("Potion".to_string()),
+            Some(ItemKind::Scroll) => lines.push("Scroll".to_string()),
+            None => {}
+        }
+
+        if let Some(healing) = ecs.read_storage::<ProvidesHealing>().get(item) {
+            lines.push(format!("Heals {} hp", healing.heal_amount));
+        }
+        if let Some(damage) = ecs.read_storage::<InflictsDamage>().get(item) {
+            lines.push(format!("Deals {} {} damage", damage.damage, damage.damage_type));
+        }
+        if let Some(confusion) = ecs.read_storage::<Confusion>().get(item) {
+            lines.push(format!("Confuses for {} turns", confusion.turns));
+        }
+        if let Some(modifies_speed) = ecs.read_storage::<ModifiesSpeed>().get(item) {
+            let effect = match modifies_speed.multiplier < 1. {
+                true => "Hastes",
+                false => "Slows",
+            };
+            lines.push(format!("{} (x{:.2} time) for {} turns", effect, modifies_speed.multiplier, modifies_speed.turns));
+        }
+        match ecs.read_storage::<Ranged>().get(item) {
+            Some(ranged) => lines.push(format!("Range {}", ranged.range)),
+            None => lines.push("Used on yourself".to_string()),
+        }
+        if let Some(area_of_effect) = ecs.read_storage::<AreaOfEffect>().get(item) {
+            lines.push(format!("Blast radius {}", area_of_effect.radius));
+        }
+
+        let use_time = ecs
+            .read_storage::<UseTimeCost>()
+            .get(item)
+            .map(|use_time_cost| use_time_cost.time_cost)
+            .or_else(|| ecs.read_storage::<CanUseItem>().get(*player_entity).map(|can_use_item| can_use_item.time_cost));
+        if let Some(use_time) = use_time {
+            lines.push(format!("Takes {} time to use", use_time));
+        }
+        if ecs.read_storage::<Consumable>().get(item).is_some() {
+            lines.push("Used up on use".to_string());
+        }
+
+        lines
+    }
+
+    fn handle_key(&mut self, key: VirtualKeyCode, page_stacks: &[&ItemStack], page_count: usize) -> ItemMenuResult {
+        let keymap = self.state.ecs.fetch::<Keymap>();
+        let mut menu_state = self.state.ecs.write_resource::<ItemMenuState>();
+
+        match key {
+            key if keymap.is_bound(InputAction::Cancel, key) => ItemMenuResult::Cancel,
+            key if keymap.is_bound(InputAction::MenuUp, key) => {
+                menu_state.cursor = menu_state.cursor.saturating_sub(1);
+                ItemMenuResult::NoResponse
+            }
+            key if keymap.is_bound(InputAction::MenuDown, key) => {
+                menu_state.cursor += 1;
+                ItemMenuResult::NoResponse
+            }
+            key if keymap.is_bound(InputAction::PreviousPage, key) => {
+                menu_state.page = menu_state.page.saturating_sub(1);
+                menu_state.cursor = 0;
+                ItemMenuResult::NoResponse
+            }
+            key if keymap.is_bound(InputAction::NextPage, key) => {
+                menu_state.page = usize::min(menu_state.page + 1, page_count - 1);
+                menu_state.cursor = 0;
+                ItemMenuResult::NoResponse
+            }
+            key if keymap.is_bound(InputAction::Confirm, key) => match page_stacks.get(menu_state.cursor) {
+                None => ItemMenuResult::NoResponse,
+                Some(stack) => ItemMenuResult::Selected(stack.entities[0]),
+            },
+            _ => {
+                let selection = rltk::letter_to_option(key) as usize;
+
+                match page_stacks.get(selection) {
+                    None => ItemMenuResult::NoResponse,
+                    Some(stack) => ItemMenuResult::Selected(stack.entities[0]),
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
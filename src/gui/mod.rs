@@ -1,7 +1,11 @@
 pub use camera_renderer::*;
+pub use character_sheet_drawer::*;
+pub use help_drawer::*;
+pub use history_drawer::*;
 pub use item_menu_drawer::*;
 pub use main_menu_drawer::*;
 pub use ranged_target_drawer::*;
+pub use timeline_drawer::*;
 pub use tooltip_drawer::*;
 pub use ui_drawer::*;
 
@@ -11,4 +15,8 @@ pub mod item_menu_drawer;
 pub mod tooltip_drawer;
 pub mod camera_renderer;
 pub mod main_menu_drawer;
+pub mod timeline_drawer;
+pub mod history_drawer;
+pub mod help_drawer;
+pub mod character_sheet_drawer;
 
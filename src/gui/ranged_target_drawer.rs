@@ -2,13 +2,28 @@ extern crate rltk;
 
 use specs::prelude::*;
 
-use crate::{Context, get_screen_bounds, Map, State, Viewshed};
+use crate::{CombatStats, Context, Faction, FactionTable, get_screen_bounds, InputAction, Keymap, Map, Position, Reaction, State, Viewshed};
 
-use self::rltk::{Algorithm2D, ColorPair, Point, RGB};
+use self::rltk::{Algorithm2D, BaseMap, ColorPair, LineAlg, Point, RGB};
 
 #[derive(PartialEq, Copy, Clone)]
 pub enum RangedTargetResult { Cancel, NoResponse, Selected(Point) }
 
+/// Keyboard cursor of the targeting mode, in map coordinates.
+/// Moving the mouse moves the cursor too, so both ways of aiming can be mixed.
+#[derive(Default)]
+pub struct TargetingCursor {
+    pub position: Option<Point>,
+    pub target_index: Option<usize>,
+    pub last_mouse: Option<Point>,
+}
+
+impl TargetingCursor {
+    pub fn reset(&mut self) {
+        *self = TargetingCursor::default();
+    }
+}
+
 pub fn ranged_target(state: &mut State, context: &mut Context, settings: RangedTargetDrawerSettings) -> RangedTargetResult {
     RangedTargetDrawer {
         state,
@@ -32,31 +47,150 @@ impl<'a, 'b> RangedTargetDrawer<'a, 'b> {
     pub fn draw_ranged_target(&mut self) -> RangedTargetResult {
         let (result_or_none, in_range_tiles) = self.draw_range();
 
-        if result_or_none.is_some() {
-            return result_or_none.unwrap();
+        if let Some(result) = result_or_none {
+            return result;
         }
 
-        let (screen_x, screen_y) = self.context.rltk.mouse_pos();
         let (min_x, _, min_y, _) = get_screen_bounds(&self.state.ecs, self.context);
-        let (map_x, map_y) = (screen_x + min_x, screen_y + min_y);
-        let is_in_range = in_range_tiles
-            .iter()
-            .any(|visible| visible.x == map_x && visible.y == map_y);
+        let targets = self.get_hostile_targets(&in_range_tiles);
 
-        if is_in_range {
-            self.draw_radius(Point::new(screen_x, screen_y));
-            self.context.set_bg(Point::new(screen_x, screen_y), RGB::named(rltk::CYAN));
-            if self.context.rltk.left_click {
-                return RangedTargetResult::Selected(Point::new(map_x, map_y));
-            }
+        if let Some(result) = self.update_cursor(&targets, min_x, min_y) {
+            return result;
+        }
+
+        let cursor = self.state.ecs.fetch::<TargetingCursor>().position.unwrap();
+        let is_in_range = in_range_tiles.contains(&cursor);
+        let is_blocked = self.draw_line_of_fire(cursor, min_x, min_y);
+        let screen_cursor = Point::new(cursor.x - min_x, cursor.y - min_y);
+
+        self.draw_status(targets.len(), is_blocked);
+
+        if is_in_range && !is_blocked {
+            self.draw_radius(screen_cursor);
+            self.context.set_bg(screen_cursor, RGB::named(rltk::CYAN));
         } else {
-            self.context.set_bg(Point::new(screen_x, screen_y), RGB::named(rltk::RED));
-            if self.context.rltk.left_click {
-                return RangedTargetResult::Cancel;
+            self.context.set_bg(screen_cursor, RGB::named(rltk::RED));
+        }
+
+        let keymap = self.state.ecs.fetch::<Keymap>();
+        let is_confirmed = self.context.rltk.left_click || self.context.rltk.key.is_some_and(|key| keymap.is_bound(InputAction::Confirm, key));
+
+        match self.context.rltk.key {
+            Some(key) if keymap.is_bound(InputAction::Cancel, key) => RangedTargetResult::Cancel,
+            _ if !is_confirmed => RangedTargetResult::NoResponse,
+            _ if is_in_range && !is_blocked => RangedTargetResult::Selected(cursor),
+            _ if self.context.rltk.left_click && !is_in_range => RangedTargetResult::Cancel,
+            _ => RangedTargetResult::NoResponse,
+        }
+    }
+
+    /// Applies mouse movement, Tab cycling and cursor keys to the targeting cursor.
+    fn update_cursor(&mut self, targets: &[Point], min_x: i32, min_y: i32) -> Option<RangedTargetResult> {
+        let player_position = *self.state.ecs.fetch::<Point>();
+        let keymap = self.state.ecs.fetch::<Keymap>();
+        let mut targeting_cursor = self.state.ecs.write_resource::<TargetingCursor>();
+
+        if targeting_cursor.position.is_none() {
+            targeting_cursor.target_index = if targets.is_empty() { None } else { Some(0) };
+            targeting_cursor.position = Some(targets.first().copied().unwrap_or(player_position));
+        }
+
+        let (mouse_x, mouse_y) = self.context.rltk.mouse_pos();
+        let mouse = Point::new(mouse_x, mouse_y);
+        if targeting_cursor.last_mouse.is_some_and(|last_mouse| last_mouse != mouse) {
+            targeting_cursor.position = Some(Point::new(mouse_x + min_x, mouse_y + min_y));
+            targeting_cursor.target_index = None;
+        }
+        targeting_cursor.last_mouse = Some(mouse);
+
+        match self.context.rltk.key {
+            Some(key) if keymap.is_bound(InputAction::NextTarget, key) && !targets.is_empty() => {
+                let next_index = targeting_cursor.target_index.map_or(0, |index| (index + 1) % targets.len());
+                targeting_cursor.target_index = Some(next_index);
+                targeting_cursor.position = Some(targets[next_index]);
             }
+            Some(key) => {
+                let delta = keymap.get_action(key).and_then(|action| action.get_move_delta());
+                if let (Some((delta_x, delta_y)), Some(position)) = (delta, targeting_cursor.position) {
+                    targeting_cursor.position = Some(Point::new(position.x + delta_x, position.y + delta_y));
+                    targeting_cursor.target_index = None;
+                }
+            }
+            None => {}
+        }
+
+        match targeting_cursor.position {
+            None => Some(RangedTargetResult::Cancel),
+            Some(_) => None,
+        }
+    }
+
+    /// Visible hostiles within range, nearest first.
+    fn get_hostile_targets(&self, in_range_tiles: &[Point]) -> Vec<Point> {
+        let player_entity = self.state.ecs.fetch::<Entity>();
+        let player_position = *self.state.ecs.fetch::<Point>();
+        let entities = self.state.ecs.entities();
+        let positions = self.state.ecs.read_storage::<Position>();
+        let combat_stats = self.state.ecs.read_storage::<CombatStats>();
+        let factions = self.state.ecs.read_storage::<Faction>();
+        let faction_table = self.state.ecs.fetch::<FactionTable>();
+
+        let mut targets: Vec<Point> = (&entities, &positions, &combat_stats)
+            .join()
+            .filter(|(entity, _, _)| *entity != *player_entity)
+            .filter(|(entity, _, _)| faction_table.get_entity_reaction(&factions, *player_entity, *entity) == Reaction::Hostile)
+            .map(|(_, position, _)| Point::new(position.x, position.y))
+            .filter(|position| in_range_tiles.contains(position))
+            .collect();
+
+        targets.sort_by(|a, b| {
+            let distance_a = rltk::DistanceAlg::Pythagoras.distance2d(player_position, *a);
+            let distance_b = rltk::DistanceAlg::Pythagoras.distance2d(player_position, *b);
+            distance_a.partial_cmp(&distance_b).unwrap()
+        });
+
+        targets
+    }
+
+    /// Draws the path a shot would take, returning whether a wall stops it short of the target.
+    fn draw_line_of_fire(&mut self, target: Point, min_x: i32, min_y: i32) -> bool {
+        let player_position = *self.state.ecs.fetch::<Point>();
+        let map = self.state.ecs.fetch::<Map>();
+
+        let mut is_blocked = false;
+        for tile in rltk::line2d(LineAlg::Bresenham, player_position, target).iter().skip(1) {
+            if *tile == target {
+                break;
+            }
+
+            if !map.in_bounds(*tile) || map.is_opaque(map.xy_idx(tile.x, tile.y)) {
+                is_blocked = true;
+            }
+
+            let path_bg = match is_blocked {
+                true => RGB::named(rltk::DARK_RED),
+                false => RGB::named(rltk::DARK_GOLDENROD),
+            };
+            self.context.set_bg(Point::new(tile.x - min_x, tile.y - min_y), path_bg);
         }
 
-        RangedTargetResult::NoResponse
+        is_blocked
+    }
+
+    fn draw_status(&mut self, target_count: usize, is_blocked: bool) {
+        let keymap = self.state.ecs.fetch::<Keymap>();
+
+        let confirm_keys = keymap.describe(InputAction::Confirm);
+        let cancel_keys = keymap.describe(InputAction::Cancel);
+        let hint = match target_count {
+            0 => format!("Select Target: {} to fire, {} to cancel", confirm_keys, cancel_keys),
+            _ => format!("Select Target: {} cycles {} targets, {} to fire, {} to cancel", keymap.describe(InputAction::NextTarget), target_count, confirm_keys, cancel_keys),
+        };
+        self.context.print_color(Point::new(5, 0), hint, ColorPair::new(RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK)));
+
+        if is_blocked {
+            self.context.print_color(Point::new(5, 1), "Line of fire blocked", ColorPair::new(RGB::named(rltk::RED), RGB::named(rltk::BLACK)));
+        }
     }
 
     fn draw_range(&mut self) -> (Option<RangedTargetResult>, Vec<Point>) {
@@ -65,15 +199,14 @@ impl<'a, 'b> RangedTargetDrawer<'a, 'b> {
         let player_position = self.state.ecs.fetch::<Point>();
         let viewsheds = self.state.ecs.read_storage::<Viewshed>();
 
-        self.context.print_color(Point::new(5, 0), "Select Target:", ColorPair::new(RGB::named(rltk::YELLOW), RGB::named(rltk::BLACK)));
-
         let player_viewshed = viewsheds.get(*player_entity);
         let mut in_range_tiles = Vec::new();
 
         match player_viewshed {
             None => return (Some(RangedTargetResult::Cancel), in_range_tiles),
             Some(player_viewshed) => {
-                for visible_tile in player_viewshed.visible_tiles.iter() {
+                // Your own tile is never a target, so a stray confirm can't land a blast at your feet.
+                for visible_tile in player_viewshed.visible_tiles.iter().filter(|tile| **tile != *player_position) {
                     let distance = rltk::DistanceAlg::Pythagoras.distance2d(*player_position, *visible_tile);
                     if distance <= self.settings.range as f32 {
                         self.context.set_bg(Point::new(visible_tile.x - min_x, visible_tile.y - min_y), RGB::named(rltk::BLUE));
@@ -110,4 +243,4 @@ impl<'a, 'b> RangedTargetDrawer<'a, 'b> {
             self.context.set_bg(screen_tile, RGB::named(rltk::ORANGE));
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,119 @@
+extern crate rltk;
+
+use specs::prelude::*;
+
+use crate::{CanMove, CONSOLE_INDEX, Context, GlobalTurn, IsVisible, Name, Player, Renderable, SpeedModifiers, TakesTurn};
+
+use self::rltk::{ColorPair, Point, Rect, RGB};
+
+const TIMELINE_LENGTH: usize = 8;
+const TIMELINE_WIDTH: i32 = 24;
+const DEFAULT_TIME_COST: u32 = 100;
+
+pub struct TimelineDrawer<'a, 'b> {
+    pub ecs: &'a World,
+    pub context: &'a mut Context<'b>,
+}
+
+struct TimelineActor {
+    entity: Entity,
+    next_time_score: u32,
+    time_cost: u32,
+}
+
+impl<'a, 'b> TimelineDrawer<'a, 'b> {
+    pub fn draw_timeline(&mut self) {
+        self.context.set_target(CONSOLE_INDEX.ui);
+
+        self.draw_timeline_internal();
+
+        self.context.set_target(CONSOLE_INDEX.base);
+    }
+
+    fn draw_timeline_internal(&mut self) {
+        let names = self.ecs.read_storage::<Name>();
+        let renderables = self.ecs.read_storage::<Renderable>();
+        let players = self.ecs.read_storage::<Player>();
+
+        let entries = self.get_upcoming_turns();
+
+        if entries.is_empty() {
+            return;
+        }
+
+        let (window_width, _) = self.context.get_screen_size();
+        let left_x = window_width as i32 - TIMELINE_WIDTH - 1;
+
+        self.context.draw_box(
+            Rect::with_size(left_x, 0, TIMELINE_WIDTH, entries.len() as i32 + 1),
+            ColorPair::new(
+                RGB::named(rltk::WHITE),
+                RGB::named(rltk::BLACK)));
+
+        self.context.print_color(
+            Point::new(left_x + 2, 0),
+            " Next turns ",
+            ColorPair::new(
+                RGB::named(rltk::YELLOW),
+                RGB::named(rltk::BLACK)));
+
+        for (i, (entity, time_until)) in entries.iter().enumerate() {
+            let name = names.get(*entity).map_or("Something", |name| &name.name);
+            let fg = match players.get(*entity) {
+                Some(_) => RGB::named(rltk::YELLOW),
+                None => renderables.get(*entity).map_or(RGB::named(rltk::WHITE), |renderable| renderable.fg),
+            };
+
+            let mut entry = format!("+{:<4} {}", time_until, name);
+            entry.truncate((TIMELINE_WIDTH - 2) as usize);
+
+            self.context.print_color(
+                Point::new(left_x + 1, i as i32 + 1),
+                entry,
+                ColorPair::new(fg, RGB::named(rltk::BLACK)));
+        }
+    }
+
+    /// Projects the scheduler forward, assuming every actor keeps spending its move cost per turn.
+    fn get_upcoming_turns(&mut self) -> Vec<(Entity, u32)> {
+        let entities = self.ecs.entities();
+        let takes_turn = self.ecs.read_storage::<TakesTurn>();
+        let can_move = self.ecs.read_storage::<CanMove>();
+        let players = self.ecs.read_storage::<Player>();
+        let is_visible = self.ecs.read_storage::<IsVisible>();
+        let global_turns = self.ecs.read_storage::<GlobalTurn>();
+        let speed_modifiers = self.ecs.read_storage::<SpeedModifiers>();
+
+        let mut actors = (&entities, &takes_turn, !&global_turns)
+            .join()
+            .filter(|(entity, _, _)| players.get(*entity).is_some() || is_visible.get(*entity).is_some())
+            .map(|(entity, takes_turn, _)| TimelineActor {
+                entity,
+                next_time_score: takes_turn.time_score,
+                time_cost: SpeedModifiers::get_time_cost(
+                    &speed_modifiers,
+                    entity,
+                    can_move.get(entity).map_or(DEFAULT_TIME_COST, |can_move| u32::max(1, can_move.time_cost))),
+            })
+            .collect::<Vec<TimelineActor>>();
+
+        let now = match actors.iter().map(|actor| actor.next_time_score).min() {
+            None => return Vec::new(),
+            Some(now) => now,
+        };
+
+        let mut entries = Vec::new();
+
+        while entries.len() < TIMELINE_LENGTH {
+            let next_actor = actors
+                .iter_mut()
+                .min_by_key(|actor| actor.next_time_score)
+                .unwrap();
+
+            entries.push((next_actor.entity, next_actor.next_time_score - now));
+            next_actor.next_time_score += next_actor.time_cost;
+        }
+
+        entries
+    }
+}
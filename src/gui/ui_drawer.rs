@@ -2,7 +2,7 @@ extern crate rltk;
 
 use specs::prelude::*;
 
-use crate::{CombatStats, Context, GameLog, Player, TooltipDrawer, TooltipOrientation};
+use crate::{CombatStats, Context, GameLog, LogEntry, Player, Sneaking, TimelineDrawer, TooltipDrawer, TooltipOrientation};
 
 use self::rltk::{ColorPair, Point, Rect, RGB};
 
@@ -41,8 +41,10 @@ impl<'a, 'b> UiDrawer<'a, 'b> {
     pub fn draw_ui(&mut self) {
         self.draw_game_log_frame();
         self.draw_health();
+        self.draw_sneaking();
         self.draw_logs();
         self.draw_mouse_cursor();
+        self.draw_timeline();
         self.draw_tooltip();
     }
 
@@ -88,13 +90,43 @@ impl<'a, 'b> UiDrawer<'a, 'b> {
         }
     }
 
+    fn draw_sneaking(&mut self) {
+        let sneaking = self.ecs.read_storage::<Sneaking>();
+        let players = self.ecs.read_storage::<Player>();
+
+        if (&players, &sneaking).join().next().is_some() {
+            self.context.print_color(
+                Point::new(
+                    LOG_ENTRY_OFFSET,
+                    self.dimensions.map_screen_height),
+                " Sneaking ",
+                ColorPair::new(
+                    RGB::named(rltk::LIGHT_BLUE),
+                    RGB::named(rltk::BLACK)),
+            );
+        }
+    }
+
     fn draw_logs(&mut self) {
         let log = self.ecs.fetch::<GameLog>();
 
+        if let Some(filter) = log.filter {
+            let filter_text = format!(" Log: {} ", filter);
+            self.context.print_color(
+                Point::new(
+                    self.dimensions.window_width - filter_text.len() as i32 - LOG_ENTRY_OFFSET,
+                    self.dimensions.map_screen_height),
+                &filter_text,
+                ColorPair::new(
+                    RGB::named(rltk::LIGHT_GRAY),
+                    RGB::named(rltk::BLACK)),
+            );
+        }
+
         let mut y = self.dimensions.window_height - 2;
-        for entry in log.entries.iter().skip(log.display_index as usize) {
+        for entry in log.visible_entries().skip(log.display_index as usize) {
             if y >= self.dimensions.window_height - GAME_LOG_HEIGHT + 1 {
-                self.context.print(Point::new(LOG_ENTRY_OFFSET, y), &entry.get_formatted_message());
+                self.draw_log_entry(entry, y);
             } else {
                 break;
             }
@@ -103,11 +135,35 @@ impl<'a, 'b> UiDrawer<'a, 'b> {
         }
     }
 
+    fn draw_log_entry(&mut self, entry: &LogEntry, y: i32) {
+        let bg = match entry.message.is_important {
+            true => RGB::named(rltk::DARK_RED),
+            false => RGB::named(rltk::BLACK),
+        };
+
+        let mut x = LOG_ENTRY_OFFSET;
+        for span in entry.message.spans.iter() {
+            self.context.print_color(Point::new(x, y), &span.text, ColorPair::new(span.color, bg));
+            x += span.text.chars().count() as i32;
+        }
+
+        if let Some(count_suffix) = entry.get_count_suffix() {
+            self.context.print_color(
+                Point::new(x, y),
+                &count_suffix,
+                ColorPair::new(RGB::named(rltk::GRAY), bg));
+        }
+    }
+
     fn draw_mouse_cursor(&mut self) {
         let (mouse_x, mouse_y) = self.context.rltk.mouse_pos();
         self.context.set_bg(Point::new(mouse_x, mouse_y), RGB::named(rltk::MAGENTA));
     }
 
+    fn draw_timeline(&mut self) {
+        TimelineDrawer { ecs: self.ecs, context: self.context }.draw_timeline();
+    }
+
     fn draw_tooltip(&mut self) {
         let (mouse_x, mouse_y) = self.context.rltk.mouse_pos();
 
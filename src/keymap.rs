@@ -0,0 +1,414 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use rltk::VirtualKeyCode;
+use serde::{Deserialize, Serialize};
+
+use crate::console_log;
+
+const KEYMAP_FILE_PATH: &str = "./keymap.json";
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Serialize, Deserialize)]
+pub enum InputAction {
+    MoveNorth,
+    MoveSouth,
+    MoveEast,
+    MoveWest,
+    MoveNorthEast,
+    MoveNorthWest,
+    MoveSouthEast,
+    MoveSouthWest,
+    Wait,
+    PickUp,
+    Inventory,
+    Drop,
+    ToggleSneak,
+    CycleLogFilter,
+    ShowHistory,
+    ScrollLogUp,
+    ScrollLogDown,
+    CharacterSheet,
+    ShowHelp,
+    SaveAndQuit,
+    MenuUp,
+    MenuDown,
+    PreviousPage,
+    NextPage,
+    NextTarget,
+    Confirm,
+    Cancel,
+}
+
+impl InputAction {
+    pub const ALL: [InputAction; 27] = [
+        InputAction::MoveNorth,
+        InputAction::MoveSouth,
+        InputAction::MoveEast,
+        InputAction::MoveWest,
+        InputAction::MoveNorthEast,
+        InputAction::MoveNorthWest,
+        InputAction::MoveSouthEast,
+        InputAction::MoveSouthWest,
+        InputAction::Wait,
+        InputAction::PickUp,
+        InputAction::Inventory,
+        InputAction::Drop,
+        InputAction::ToggleSneak,
+        InputAction::CycleLogFilter,
+        InputAction::ShowHistory,
+        InputAction::ScrollLogUp,
+        InputAction::ScrollLogDown,
+        InputAction::CharacterSheet,
+        InputAction::ShowHelp,
+        InputAction::SaveAndQuit,
+        InputAction::MenuUp,
+        InputAction::MenuDown,
+        InputAction::PreviousPage,
+        InputAction::NextPage,
+        InputAction::NextTarget,
+        InputAction::Confirm,
+        InputAction::Cancel,
+    ];
+
+    /// Menu actions share keys with gameplay actions, so they are only looked up inside menus.
+    pub fn is_menu_action(&self) -> bool {
+        matches!(self, InputAction::MenuUp | InputAction::MenuDown | InputAction::PreviousPage | InputAction::NextPage | InputAction::NextTarget | InputAction::Confirm | InputAction::Cancel)
+    }
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            InputAction::MoveNorth => "Move north",
+            InputAction::MoveSouth => "Move south",
+            InputAction::MoveEast => "Move east",
+            InputAction::MoveWest => "Move west",
+            InputAction::MoveNorthEast => "Move north-east",
+            InputAction::MoveNorthWest => "Move north-west",
+            InputAction::MoveSouthEast => "Move south-east",
+            InputAction::MoveSouthWest => "Move south-west",
+            InputAction::Wait => "Wait a turn",
+            InputAction::PickUp => "Pick up item",
+            InputAction::Inventory => "Use item",
+            InputAction::Drop => "Drop item",
+            InputAction::ToggleSneak => "Toggle sneaking",
+            InputAction::CycleLogFilter => "Filter log",
+            InputAction::ShowHistory => "Message history",
+            InputAction::ScrollLogUp => "Scroll log back",
+            InputAction::ScrollLogDown => "Scroll log forward",
+            InputAction::CharacterSheet => "Character sheet",
+            InputAction::ShowHelp => "Show this help",
+            InputAction::SaveAndQuit => "Save and quit",
+            InputAction::MenuUp => "Menu up",
+            InputAction::MenuDown => "Menu down",
+            InputAction::PreviousPage => "Previous page",
+            InputAction::NextPage => "Next page",
+            InputAction::NextTarget => "Next target",
+            InputAction::Confirm => "Confirm choice",
+            InputAction::Cancel => "Close menu",
+        }
+    }
+
+    pub fn get_move_delta(&self) -> Option<(i32, i32)> {
+        match self {
+            InputAction::MoveNorth => Some((0, -1)),
+            InputAction::MoveSouth => Some((0, 1)),
+            InputAction::MoveEast => Some((1, 0)),
+            InputAction::MoveWest => Some((-1, 0)),
+            InputAction::MoveNorthEast => Some((1, -1)),
+            InputAction::MoveNorthWest => Some((-1, -1)),
+            InputAction::MoveSouthEast => Some((1, 1)),
+            InputAction::MoveSouthWest => Some((-1, 1)),
+            _ => None,
+        }
+    }
+
+    fn get_default_keys(&self) -> Vec<VirtualKeyCode> {
+        match self {
+            InputAction::MoveNorth => vec![VirtualKeyCode::Up, VirtualKeyCode::K, VirtualKeyCode::Numpad8],
+            InputAction::MoveSouth => vec![VirtualKeyCode::Down, VirtualKeyCode::J, VirtualKeyCode::Numpad2],
+            InputAction::MoveEast => vec![VirtualKeyCode::Right, VirtualKeyCode::L, VirtualKeyCode::Numpad6],
+            InputAction::MoveWest => vec![VirtualKeyCode::Left, VirtualKeyCode::H, VirtualKeyCode::Numpad4],
+            InputAction::MoveNorthEast => vec![VirtualKeyCode::U, VirtualKeyCode::Numpad9],
+            InputAction::MoveNorthWest => vec![VirtualKeyCode::Y, VirtualKeyCode::Numpad7],
+            InputAction::MoveSouthEast => vec![VirtualKeyCode::N, VirtualKeyCode::Numpad3],
+            InputAction::MoveSouthWest => vec![VirtualKeyCode::B, VirtualKeyCode::Numpad1],
+            InputAction::Wait => vec![VirtualKeyCode::W, VirtualKeyCode::Period, VirtualKeyCode::Numpad5],
+            InputAction::PickUp => vec![VirtualKeyCode::G, VirtualKeyCode::Comma],
+            InputAction::Inventory => vec![VirtualKeyCode::I],
+            InputAction::Drop => vec![VirtualKeyCode::D],
+            InputAction::ToggleSneak => vec![VirtualKeyCode::S],
+            InputAction::CycleLogFilter => vec![VirtualKeyCode::F],
+            InputAction::ShowHistory => vec![VirtualKeyCode::M],
+            InputAction::ScrollLogUp => vec![VirtualKeyCode::PageUp],
+            InputAction::ScrollLogDown => vec![VirtualKeyCode::PageDown],
+            InputAction::CharacterSheet => vec![VirtualKeyCode::C],
+            InputAction::ShowHelp => vec![VirtualKeyCode::Slash, VirtualKeyCode::F1],
+            InputAction::SaveAndQuit => vec![VirtualKeyCode::Escape],
+            InputAction::MenuUp => vec![VirtualKeyCode::Up],
+            InputAction::MenuDown => vec![VirtualKeyCode::Down],
+            InputAction::PreviousPage => vec![VirtualKeyCode::PageUp],
+            InputAction::NextPage => vec![VirtualKeyCode::PageDown],
+            InputAction::NextTarget => vec![VirtualKeyCode::Tab],
+            InputAction::Confirm => vec![VirtualKeyCode::Return],
+            InputAction::Cancel => vec![VirtualKeyCode::Escape],
+        }
+    }
+}
+
+const KEY_NAMES: [(&str, VirtualKeyCode); 68] = [
+    ("A", VirtualKeyCode::A),
+    ("B", VirtualKeyCode::B),
+    ("C", VirtualKeyCode::C),
+    ("D", VirtualKeyCode::D),
+    ("E", VirtualKeyCode::E),
+    ("F", VirtualKeyCode::F),
+    ("G", VirtualKeyCode::G),
+    ("H", VirtualKeyCode::H),
+    ("I", VirtualKeyCode::I),
+    ("J", VirtualKeyCode::J),
+    ("K", VirtualKeyCode::K),
+    ("L", VirtualKeyCode::L),
+    ("M", VirtualKeyCode::M),
+    ("N", VirtualKeyCode::N),
+    ("O", VirtualKeyCode::O),
+    ("P", VirtualKeyCode::P),
+    ("Q", VirtualKeyCode::Q),
+    ("R", VirtualKeyCode::R),
+    ("S", VirtualKeyCode::S),
+    ("T", VirtualKeyCode::T),
+    ("U", VirtualKeyCode::U),
+    ("V", VirtualKeyCode::V),
+    ("W", VirtualKeyCode::W),
+    ("X", VirtualKeyCode::X),
+    ("Y", VirtualKeyCode::Y),
+    ("Z", VirtualKeyCode::Z),
+    ("0", VirtualKeyCode::Key0),
+    ("1", VirtualKeyCode::Key1),
+    ("2", VirtualKeyCode::Key2),
+    ("3", VirtualKeyCode::Key3),
+    ("4", VirtualKeyCode::Key4),
+    ("5", VirtualKeyCode::Key5),
+    ("6", VirtualKeyCode::Key6),
+    ("7", VirtualKeyCode::Key7),
+    ("8", VirtualKeyCode::Key8),
+    ("9", VirtualKeyCode::Key9),
+    ("Numpad0", VirtualKeyCode::Numpad0),
+    ("Numpad1", VirtualKeyCode::Numpad1),
+    ("Numpad2", VirtualKeyCode::Numpad2),
+    ("Numpad3", VirtualKeyCode::Numpad3),
+    ("Numpad4", VirtualKeyCode::Numpad4),
+    ("Numpad5", VirtualKeyCode::Numpad5),
+    ("Numpad6", VirtualKeyCode::Numpad6),
+    ("Numpad7", VirtualKeyCode::Numpad7),
+    ("Numpad8", VirtualKeyCode::Numpad8),
+    ("Numpad9", VirtualKeyCode::Numpad9),
+    ("Up", VirtualKeyCode::Up),
+    ("Down", VirtualKeyCode::Down),
+    ("Left", VirtualKeyCode::Left),
+    ("Right", VirtualKeyCode::Right),
+    ("Escape", VirtualKeyCode::Escape),
+    ("Return", VirtualKeyCode::Return),
+    ("Space", VirtualKeyCode::Space),
+    ("Tab", VirtualKeyCode::Tab),
+    ("Back", VirtualKeyCode::Back),
+    ("PageUp", VirtualKeyCode::PageUp),
+    ("PageDown", VirtualKeyCode::PageDown),
+    ("Home", VirtualKeyCode::Home),
+    ("End", VirtualKeyCode::End),
+    ("Period", VirtualKeyCode::Period),
+    ("Comma", VirtualKeyCode::Comma),
+    ("Slash", VirtualKeyCode::Slash),
+    ("Semicolon", VirtualKeyCode::Semicolon),
+    ("F1", VirtualKeyCode::F1),
+    ("F2", VirtualKeyCode::F2),
+    ("F3", VirtualKeyCode::F3),
+    ("F4", VirtualKeyCode::F4),
+    ("F5", VirtualKeyCode::F5),
+];
+
+pub fn key_from_name(name: &str) -> Option<VirtualKeyCode> {
+    KEY_NAMES
+        .iter()
+        .find(|(key_name, _)| key_name.eq_ignore_ascii_case(name))
+        .map(|(_, key)| *key)
+}
+
+pub fn key_name(key: VirtualKeyCode) -> &'static str {
+    KEY_NAMES
+        .iter()
+        .find(|(_, named_key)| *named_key == key)
+        .map_or("?", |(name, _)| name)
+}
+
+/// Maps keys to actions.
+/// Actions missing from the keymap file keep their default arrow, vi-key and numpad bindings.
+pub struct Keymap {
+    bindings: HashMap<InputAction, Vec<VirtualKeyCode>>,
+}
+
+impl Keymap {
+    pub fn new() -> Keymap {
+        let bindings = InputAction::ALL
+            .iter()
+            .map(|action| (*action, action.get_default_keys()))
+            .collect();
+
+        Keymap { bindings }
+    }
+
+    /// Reads the keymap file, writing out the defaults first if there is none so players have something to edit.
+    pub fn load() -> Keymap {
+        let mut keymap = Keymap::new();
+
+        if !Path::new(KEYMAP_FILE_PATH).exists() {
+            keymap.save();
+            return keymap;
+        }
+
+        let file_bindings = fs::read_to_string(KEYMAP_FILE_PATH)
+            .map_err(|error| error.to_string())
+            .and_then(|data| {
+                serde_json::from_str::<BTreeMap<InputAction, Vec<String>>>(&data).map_err(|error| error.to_string())
+            });
+
+        match file_bindings {
+            Err(error) => console_log(format!("Unable to read {}, using default keys: {}", KEYMAP_FILE_PATH, error)),
+            Ok(file_bindings) => keymap.apply_file_bindings(file_bindings),
+        }
+
+        for (key, actions) in keymap.get_duplicate_bindings() {
+            console_log(format!("{} is bound to {:?}, only {:?} will be used", key_name(key), actions, actions[0]));
+        }
+
+        keymap
+    }
+
+    /// Replaces the keys of every action listed in the file, leaving the others on their defaults.
+    fn apply_file_bindings(&mut self, file_bindings: BTreeMap<InputAction, Vec<String>>) {
+        for (action, key_names) in file_bindings {
+            let keys = key_names
+                .iter()
+                .filter_map(|name| {
+                    let key = key_from_name(name);
+                    if key.is_none() {
+                        console_log(format!("Unknown key \"{}\" bound to {:?}", name, action));
+                    }
+                    key
+                })
+                .collect();
+
+            self.bindings.insert(action, keys);
+        }
+    }
+
+    /// Keys bound to more than one gameplay action, with those actions in the order `get_action` tries them.
+    fn get_duplicate_bindings(&self) -> Vec<(VirtualKeyCode, Vec<InputAction>)> {
+        let mut key_actions: Vec<(VirtualKeyCode, Vec<InputAction>)> = Vec::new();
+
+        for action in InputAction::ALL.iter().filter(|action| !action.is_menu_action()) {
+            for key in self.bindings.get(action).into_iter().flatten() {
+                match key_actions.iter_mut().find(|(bound_key, _)| bound_key == key) {
+                    Some((_, actions)) if !actions.contains(action) => actions.push(*action),
+                    Some(_) => {}
+                    None => key_actions.push((*key, vec![*action])),
+                }
+            }
+        }
+
+        key_actions.retain(|(_, actions)| actions.len() > 1);
+        key_actions
+    }
+
+    fn save(&self) {
+        let file_bindings: BTreeMap<InputAction, Vec<&str>> = self.bindings
+            .iter()
+            .map(|(action, keys)| (*action, keys.iter().map(|key| key_name(*key)).collect()))
+            .collect();
+
+        let written = serde_json::to_string_pretty(&file_bindings)
+            .map_err(|error| error.to_string())
+            .and_then(|data| fs::write(KEYMAP_FILE_PATH, data).map_err(|error| error.to_string()));
+
+        if let Err(error) = written {
+            console_log(format!("Unable to write {}: {}", KEYMAP_FILE_PATH, error));
+        }
+    }
+
+    pub fn get_action(&self, key: VirtualKeyCode) -> Option<InputAction> {
+        InputAction::ALL
+            .iter()
+            .filter(|action| !action.is_menu_action())
+            .find(|action| self.is_bound(**action, key))
+            .copied()
+    }
+
+    pub fn is_bound(&self, action: InputAction, key: VirtualKeyCode) -> bool {
+        self.bindings.get(&action).is_some_and(|keys| keys.contains(&key))
+    }
+
+    pub fn describe(&self, action: InputAction) -> String {
+        match self.bindings.get(&action) {
+            Some(keys) if !keys.is_empty() => keys
+                .iter()
+                .map(|key| key_name(*key))
+                .collect::<Vec<&str>>()
+                .join("/"),
+            _ => "unbound".to_string(),
+        }
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rltk::VirtualKeyCode;
+
+    use crate::{InputAction, key_from_name, key_name, Keymap};
+
+    use super::KEY_NAMES;
+
+    #[test]
+    fn key_names_round_trip() {
+        for (name, key) in KEY_NAMES.iter() {
+            assert_eq!(key_name(*key), *name);
+            assert_eq!(key_from_name(name), Some(*key));
+            assert_eq!(key_from_name(&name.to_lowercase()), Some(*key));
+        }
+
+        assert_eq!(key_from_name("NoSuchKey"), None);
+    }
+
+    #[test]
+    fn file_bindings_replace_only_listed_actions() {
+        let mut keymap = Keymap::new();
+        let mut file_bindings = BTreeMap::new();
+        file_bindings.insert(InputAction::Wait, vec!["Z".to_string(), "NoSuchKey".to_string()]);
+
+        keymap.apply_file_bindings(file_bindings);
+
+        assert!(keymap.is_bound(InputAction::Wait, VirtualKeyCode::Z));
+        assert!(!keymap.is_bound(InputAction::Wait, VirtualKeyCode::W));
+        assert_eq!(keymap.describe(InputAction::Wait), "Z");
+        assert!(keymap.is_bound(InputAction::MoveNorth, VirtualKeyCode::K));
+    }
+
+    #[test]
+    fn detects_keys_bound_to_two_actions() {
+        let mut keymap = Keymap::new();
+        assert!(keymap.get_duplicate_bindings().is_empty());
+
+        let mut file_bindings = BTreeMap::new();
+        file_bindings.insert(InputAction::PickUp, vec!["W".to_string()]);
+        keymap.apply_file_bindings(file_bindings);
+
+        assert_eq!(keymap.get_duplicate_bindings(), vec![(VirtualKeyCode::W, vec![InputAction::Wait, InputAction::PickUp])]);
+        assert_eq!(keymap.get_action(VirtualKeyCode::W), Some(InputAction::Wait));
+    }
+}
@@ -13,8 +13,11 @@ use specs::WorldExt;
 
 pub use components::*;
 pub use context::*;
+pub use faction::*;
+pub use game_event::*;
 pub use game_log::*;
 pub use gui::*;
+pub use keymap::*;
 pub use map::*;
 pub use player::*;
 pub use random::*;
@@ -34,9 +37,12 @@ mod random;
 mod spawner;
 mod gui;
 mod game_log;
+mod game_event;
 mod context;
 mod turn_decider;
 mod save_load_system;
+mod faction;
+mod keymap;
 
 pub const DEBUG: bool = true;
 pub const TITLE: &str = "Goblin War Party";
@@ -50,8 +56,17 @@ fn main() {
 
     let mut state = State { ecs: World::new(), systems: SysRunner::new() };
     state.ecs.insert(RunStateHolder { run_state: RunState::PreRun });
-    state.ecs.insert(GameLog::new_with_first_log(format!("Welcome to {}", TITLE)));
+    state.ecs.insert(GameLog::new_with_first_log(
+        LogMessage::new(LogCategory::System).text("Welcome to ").colored(TITLE, rltk::RGB::named(rltk::GOLD))));
     state.ecs.insert(ParticleBuilder::new());
+    state.ecs.insert(FactionTable::new());
+    state.ecs.insert(NoiseBuilder::new());
+    state.ecs.insert(ActionRecorder::new());
+    state.ecs.insert(GameEvents::new());
+    state.ecs.insert(HistoryViewer::default());
+    state.ecs.insert(ItemMenuState::default());
+    state.ecs.insert(TargetingCursor::default());
+    state.ecs.insert(Keymap::load());
 
     state.ecs.register::<Position>();
     state.ecs.register::<Renderable>();
@@ -63,6 +78,34 @@ fn main() {
     state.ecs.register::<CombatStats>();
     state.ecs.register::<WantsToMelee>();
     state.ecs.register::<SuffersDamage>();
+    state.ecs.register::<KilledBy>();
+    state.ecs.register::<LootTable>();
+    state.ecs.register::<Corpse>();
+    state.ecs.register::<PartyLeader>();
+    state.ecs.register::<PartyMember>();
+    state.ecs.register::<Ally>();
+    state.ecs.register::<Awareness>();
+    state.ecs.register::<Sneaking>();
+    state.ecs.register::<AiProfile>();
+    state.ecs.register::<SpeedModifiers>();
+    state.ecs.register::<ModifiesSpeed>();
+    state.ecs.register::<CanUseItem>();
+    state.ecs.register::<CanPickUp>();
+    state.ecs.register::<CanDrop>();
+    state.ecs.register::<UseTimeCost>();
+    state.ecs.register::<Statistics>();
+    state.ecs.register::<Recruitable>();
+    state.ecs.register::<Scattering>();
+    state.ecs.register::<PlayerMemory>();
+    state.ecs.register::<Morale>();
+    state.ecs.register::<CanShoot>();
+    state.ecs.register::<WantsToShoot>();
+    state.ecs.register::<WantsToSwap>();
+    state.ecs.register::<WantsToRecruit>();
+    state.ecs.register::<Faction>();
+    state.ecs.register::<DamageResistance>();
+    state.ecs.register::<DamageImmunity>();
+    state.ecs.register::<DamageVulnerability>();
     state.ecs.register::<Item>();
     state.ecs.register::<InBackpack>();
     state.ecs.register::<WantsToPickUp>();
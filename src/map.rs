@@ -1,4 +1,5 @@
 use std::cmp::{max, min};
+use std::collections::{HashSet, VecDeque};
 
 use rltk::{Algorithm2D, BaseMap, Point, Rect};
 use serde::{Deserialize, Serialize};
@@ -10,6 +11,7 @@ use crate::RNG;
 pub enum TileType {
     Wall,
     Floor,
+    Mud,
 }
 
 #[derive(Default, Serialize, Deserialize, Clone, Debug)]
@@ -108,6 +110,38 @@ impl Map {
         }
     }
 
+    /// Walks outward from `start_idx` through every non-wall tile, ignoring anything standing in the way.
+    pub fn get_tiles_within_steps(&self, start_idx: usize, steps: i32) -> HashSet<usize> {
+        let mut reached = HashSet::new();
+        let mut frontier = VecDeque::new();
+
+        reached.insert(start_idx);
+        frontier.push_back((start_idx, 0));
+
+        while let Some((idx, distance)) = frontier.pop_front() {
+            if distance >= steps {
+                continue;
+            }
+
+            let pt = self.index_to_point2d(idx);
+            for (delta_x, delta_y) in [(0, -1), (0, 1), (1, 0), (-1, 0)].iter() {
+                let next_x = pt.x + delta_x;
+                let next_y = pt.y + delta_y;
+
+                if !self.is_valid(next_x, next_y) {
+                    continue;
+                }
+
+                let next_idx = self.xy_idx(next_x, next_y);
+                if self.tiles[next_idx] != TileType::Wall && reached.insert(next_idx) {
+                    frontier.push_back((next_idx, distance + 1));
+                }
+            }
+        }
+
+        reached
+    }
+
     pub fn clear_content_index(&mut self) {
         for content in self.tile_content.iter_mut() {
             content.clear();
@@ -202,6 +236,7 @@ pub fn new_map_rooms_and_corridors(width: i32, height: i32) -> Map {
     const MIN_SIZE: i32 = 6;
     const MAX_SIZE: i32 = 10;
     const FRAME_WIDTH: i32 = 1;
+    const MUD_CHANCE: i32 = 4;
 
     for _i in 0..MAX_ROOMS {
         let w = RNG.range(MIN_SIZE, MAX_SIZE);
@@ -215,6 +250,10 @@ pub fn new_map_rooms_and_corridors(width: i32, height: i32) -> Map {
         if ok {
             apply_room_to_map(&mut map, &new_room);
 
+            if !map.rooms.is_empty() && RNG.roll_die(MUD_CHANCE) == 1 {
+                apply_mud_to_room(&mut map, &new_room);
+            }
+
             let prev_room_or_none = map.rooms.last();
 
             if let Some(prev_room) = prev_room_or_none {
@@ -242,6 +281,21 @@ pub fn new_map_rooms_and_corridors(width: i32, height: i32) -> Map {
         }
     }
 
+    fn apply_mud_to_room(map: &mut Map, room: &Rect) {
+        let center = Point::new(
+            RNG.inclusive_range(room.x1 + 2, room.x2 - 1),
+            RNG.inclusive_range(room.y1 + 2, room.y2 - 1));
+        let radius = RNG.inclusive_range(1, 2) as f32;
+
+        for y in room.y1 + 1..=room.y2 {
+            for x in room.x1 + 1..=room.x2 {
+                if rltk::DistanceAlg::Pythagoras.distance2d(center, Point::new(x, y)) <= radius {
+                    map.set(x, y, TileType::Mud);
+                }
+            }
+        }
+    }
+
     fn apply_horizontal_tunnel(map: &mut Map, x1: i32, x2: i32, y: i32) {
         for x in min(x1, x2)..=max(x1, x2) {
             map.safe_set(x, y, TileType::Floor);
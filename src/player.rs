@@ -1,40 +1,61 @@
-use rltk::{Point, VirtualKeyCode};
+use rltk::{Point, RGB};
 use specs::prelude::*;
 
-use crate::{console_log, Context, GameLog, Item, Map, RunState, WaitCause, WantsToMelee, WantsToMove, WantsToPickUp, WantsToWait};
+use crate::{console_log, Context, Faction, FactionTable, GameLog, HistoryViewer, InputAction, Item, ItemMenuState, Keymap, LogCategory, LogMessage, Map, Reaction, Recruitable, RunState, Sneaking, WaitCause, WantsToMelee, WantsToMove, WantsToPickUp, WantsToRecruit, WantsToSwap, WantsToWait};
 
 use super::{CombatStats, Player, Position, State};
 
 pub fn player_input(state: &mut State, context: &mut Context) -> RunState {
-    match context.rltk.key {
-        None => { return RunState::AwaitingInput; }
-        Some(key) => match key {
-            VirtualKeyCode::Left |
-            VirtualKeyCode::H => try_move_player(-1, 0, &mut state.ecs),
-
-            VirtualKeyCode::Right |
-            VirtualKeyCode::L => try_move_player(1, 0, &mut state.ecs),
-
-            VirtualKeyCode::Up |
-            VirtualKeyCode::J => try_move_player(0, -1, &mut state.ecs),
-
-            VirtualKeyCode::Down |
-            VirtualKeyCode::K => try_move_player(0, 1, &mut state.ecs),
-            VirtualKeyCode::G => get_item(&mut state.ecs),
-            VirtualKeyCode::I => return RunState::ShowInventory,
-            VirtualKeyCode::D => return RunState::ShowDropItem,
-            VirtualKeyCode::W => wait(&mut state.ecs),
-            VirtualKeyCode::PageUp => {
-                try_scroll_game_log(&mut state.ecs, 1);
-                return RunState::AwaitingInput;
+    let action = match context.rltk.key {
+        None => return RunState::AwaitingInput,
+        Some(key) => state.ecs.fetch::<Keymap>().get_action(key),
+    };
+
+    match action {
+        None => return RunState::AwaitingInput,
+        Some(action) => {
+            if let Some((delta_x, delta_y)) = action.get_move_delta() {
+                try_move_player(delta_x, delta_y, &mut state.ecs);
+                return RunState::PlayerTurn;
             }
-            VirtualKeyCode::PageDown => {
-                try_scroll_game_log(&mut state.ecs, -1);
-                return RunState::AwaitingInput;
+
+            match action {
+                InputAction::PickUp => get_item(&mut state.ecs),
+                InputAction::Inventory => {
+                    state.ecs.write_resource::<ItemMenuState>().reset();
+                    return RunState::ShowInventory;
+                }
+                InputAction::Drop => {
+                    state.ecs.write_resource::<ItemMenuState>().reset();
+                    return RunState::ShowDropItem;
+                }
+                InputAction::Wait => wait(&mut state.ecs),
+                InputAction::ToggleSneak => {
+                    toggle_sneaking(&mut state.ecs);
+                    return RunState::AwaitingInput;
+                }
+                InputAction::ShowHistory => {
+                    state.ecs.write_resource::<HistoryViewer>().reset();
+                    return RunState::ShowHistory;
+                }
+                InputAction::CycleLogFilter => {
+                    cycle_game_log_filter(&mut state.ecs);
+                    return RunState::AwaitingInput;
+                }
+                InputAction::ScrollLogUp => {
+                    try_scroll_game_log(&mut state.ecs, 1);
+                    return RunState::AwaitingInput;
+                }
+                InputAction::ScrollLogDown => {
+                    try_scroll_game_log(&mut state.ecs, -1);
+                    return RunState::AwaitingInput;
+                }
+                InputAction::CharacterSheet => return RunState::ShowCharacterSheet,
+                InputAction::ShowHelp => return RunState::ShowHelp,
+                InputAction::SaveAndQuit => return RunState::SaveGame,
+                _ => return RunState::AwaitingInput,
             }
-            VirtualKeyCode::Escape => return RunState::SaveGame,
-            _ => return RunState::AwaitingInput,
-        },
+        }
     }
 
     RunState::PlayerTurn
@@ -50,16 +71,34 @@ pub fn wait(ecs: &mut World) {
     }
 }
 
+fn toggle_sneaking(ecs: &mut World) {
+    let player_entity = ecs.fetch::<Entity>();
+    let mut sneaking = ecs.write_storage::<Sneaking>();
+    let mut game_log = ecs.fetch_mut::<GameLog>();
+
+    if sneaking.remove(*player_entity).is_some() {
+        game_log.add(LogMessage::new(LogCategory::Status).text("You stop sneaking."));
+    } else {
+        sneaking.insert(*player_entity, Sneaking).expect("Unable to insert sneaking");
+        game_log.add(LogMessage::new(LogCategory::Status).text("You begin ").colored("sneaking", RGB::named(rltk::LIGHT_BLUE)).text("."));
+    }
+}
+
 pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
     let mut positions = ecs.write_storage::<Position>();
     let players = ecs.read_storage::<Player>();
 
     let mut wants_to_melee = ecs.write_storage::<WantsToMelee>();
     let mut wants_to_move = ecs.write_storage::<WantsToMove>();
+    let mut wants_to_swap = ecs.write_storage::<WantsToSwap>();
+    let mut wants_to_recruit = ecs.write_storage::<WantsToRecruit>();
     let combat_stats = ecs.read_storage::<CombatStats>();
+    let factions = ecs.read_storage::<Faction>();
+    let recruitables = ecs.read_storage::<Recruitable>();
     let entities = ecs.entities();
 
     let map = ecs.fetch::<Map>();
+    let faction_table = ecs.fetch::<FactionTable>();
 
     for (entity, _player, pos) in (&entities, &players, &mut positions).join() {
         let new_x = pos.x + delta_x;
@@ -78,9 +117,26 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
             let target_or_none = combat_stats.get(potential_target);
             let is_target = target_or_none.is_some();
             if is_target {
-                wants_to_melee
-                    .insert(entity, WantsToMelee { target: potential_target })
-                    .expect("Unable to insert intent");
+                let reaction = faction_table.get_entity_reaction(&factions, entity, potential_target);
+                let is_recruitable = recruitables.get(potential_target).is_some();
+
+                match (reaction, is_recruitable) {
+                    (Reaction::Friendly, _) => {
+                        wants_to_swap
+                            .insert(entity, WantsToSwap { target: potential_target })
+                            .expect("Unable to insert intent");
+                    }
+                    (Reaction::Neutral, true) => {
+                        wants_to_recruit
+                            .insert(entity, WantsToRecruit { target: potential_target })
+                            .expect("Unable to insert intent");
+                    }
+                    _ => {
+                        wants_to_melee
+                            .insert(entity, WantsToMelee { target: potential_target })
+                            .expect("Unable to insert intent");
+                    }
+                }
                 return;
             }
         }
@@ -98,6 +154,12 @@ fn try_scroll_game_log(ecs: &mut World, delta: i32) {
     game_log.move_index(delta);
 }
 
+fn cycle_game_log_filter(ecs: &mut World) {
+    let mut game_log = ecs.write_resource::<GameLog>();
+
+    game_log.cycle_filter();
+}
+
 fn get_item(ecs: &mut World) {
     let player_position = ecs.fetch::<Point>();
     let player_entity = ecs.fetch::<Entity>();
@@ -116,7 +178,7 @@ fn get_item(ecs: &mut World) {
     }
 
     match picked_up_item_or_none {
-        None => game_log.add("There is nothing to pick up.".to_string()),
+        None => game_log.add(LogMessage::new(LogCategory::System).text("There is nothing to pick up.")),
         Some(entity) => {
             wants_to_pick_up
                 .insert(entity, WantsToPickUp { collected_by: *player_entity, item: entity })
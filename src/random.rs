@@ -1,6 +1,6 @@
 use std::sync::Mutex;
 
-use rltk::RandomNumberGenerator;
+use rltk::{DiceType, RandomNumberGenerator};
 
 use crate::console_log;
 
@@ -57,4 +57,19 @@ impl Random {
     pub fn roll_die(&self, die_type: i32) -> i32 {
         self.rng.lock().unwrap().roll_dice(1, die_type)
     }
+
+    pub fn roll(&self, dice: DiceType) -> i32 {
+        self.rng.lock().unwrap().roll(dice)
+    }
+
+    /// Returns a random number between 0 (inclusive) and 1 (exclusive).
+    pub fn fraction(&self) -> f32 {
+        self.rng.lock().unwrap().rand::<f32>()
+    }
+}
+
+pub fn parse_dice<S: ToString>(dice: S) -> DiceType {
+    let dice = dice.to_string();
+
+    rltk::parse_dice_string(&dice).unwrap_or_else(|_| panic!("Invalid dice string: {}", dice))
 }
\ No newline at end of file
@@ -7,7 +7,7 @@ use specs::error::NoError;
 use specs::prelude::*;
 use specs::saveload::{DeserializeComponents, MarkedBuilder, SerializeComponents, SimpleMarker, SimpleMarkerAllocator};
 
-use crate::{Map, SerializeMe};
+use crate::{GameLog, GlobalTurnTimeScore, Map, SerializeMe};
 use crate::components::*;
 
 const SAVE_FILE_PATH: &str = "./save_game.json";
@@ -51,7 +51,34 @@ pub fn save_game(ecs: &mut World) {
             BlocksTile,
             CombatStats,
             WantsToMelee,
-            SuffersDamage,
+            KilledBy,
+            LootTable,
+            Corpse,
+            PartyLeader,
+            PartyMember,
+            Ally,
+            Awareness,
+            Sneaking,
+            AiProfile,
+            SpeedModifiers,
+            ModifiesSpeed,
+            CanUseItem,
+            CanPickUp,
+            CanDrop,
+            UseTimeCost,
+            Statistics,
+            Recruitable,
+            Scattering,
+            PlayerMemory,
+            Morale,
+            CanShoot,
+            WantsToShoot,
+            WantsToSwap,
+            WantsToRecruit,
+            Faction,
+            DamageResistance,
+            DamageImmunity,
+            DamageVulnerability,
             Item,
             InBackpack,
             WantsToPickUp,
@@ -130,7 +157,34 @@ pub fn load_game(ecs: &mut World) {
             BlocksTile,
             CombatStats,
             WantsToMelee,
-            SuffersDamage,
+            KilledBy,
+            LootTable,
+            Corpse,
+            PartyLeader,
+            PartyMember,
+            Ally,
+            Awareness,
+            Sneaking,
+            AiProfile,
+            SpeedModifiers,
+            ModifiesSpeed,
+            CanUseItem,
+            CanPickUp,
+            CanDrop,
+            UseTimeCost,
+            Statistics,
+            Recruitable,
+            Scattering,
+            PlayerMemory,
+            Morale,
+            CanShoot,
+            WantsToShoot,
+            WantsToSwap,
+            WantsToRecruit,
+            Faction,
+            DamageResistance,
+            DamageImmunity,
+            DamageVulnerability,
             Item,
             InBackpack,
             WantsToPickUp,
@@ -178,6 +232,15 @@ pub fn load_game(ecs: &mut World) {
             let mut player_entity = ecs.write_resource::<Entity>();
             *player_entity = entity;
         }
+
+        let global_turns = ecs.read_storage::<GlobalTurn>();
+        let takes_turn = ecs.read_storage::<TakesTurn>();
+
+        for (global_turn, takes_turn) in (&global_turns, &takes_turn).join() {
+            let mut global_turn_time_score = ecs.write_resource::<GlobalTurnTimeScore>();
+            *global_turn_time_score = GlobalTurnTimeScore { time_score: takes_turn.time_score, turn: global_turn.turn };
+            ecs.write_resource::<GameLog>().current_turn = global_turn.turn;
+        }
     }
 
     ecs.delete_entity(delete_me.unwrap()).expect("Unable to delete helper");
@@ -3,11 +3,17 @@ use specs::{Entity, World};
 use specs::prelude::*;
 use specs::saveload::{MarkedBuilder, SimpleMarker};
 
-use crate::{AreaOfEffect, BlocksTile, CanMelee, CanMove, CombatStats, Confusion, Consumable, DEBUG, GlobalTurn, GlobalTurnTimeScore, InBackpack, InflictsDamage, Item, Map, Monster, Name, Player, Position, ProvidesHealing, Ranged, Renderable, RNG, SerializeMe, TakesTurn, Viewshed};
+use crate::{AiProfile, AreaOfEffect, Awareness, AwarenessState, BlocksTile, CanDrop, CanMelee, CanMove, CanPickUp, CanShoot, CanUseItem, CombatStats, Confusion, Consideration, console_log, Consumable, Corpse, DamageResistance, DamageType, DamageVulnerability, DEBUG, Faction, GlobalTurn, GlobalTurnTimeScore, GOBLIN_FACTION, InBackpack, InflictsDamage, Item, ItemKind, LootDrop, LootTable, Map, ModifiesSpeed, Monster, Morale, Name, ORC_FACTION, OUTCAST_FACTION, parse_dice, PartyLeader, PartyMember, Player, PLAYER_FACTION, Position, ProvidesHealing, Ranged, Recruitable, Renderable, RNG, SerializeMe, SpeedModifiers, SpeedSource, Statistics, TakesTurn, UseTimeCost, UtilityAction, Viewshed};
 
 const MAX_MONSTERS: i32 = 4;
 const MAX_ITEMS: i32 = 2;
 const DROP_OFFSET: i32 = 3;
+const WAR_PARTY_CHANCE: i32 = 4;
+const MONSTER_MORALE: i32 = 10;
+const SLEEP_CHANCE: i32 = 3;
+const SCROLL_USE_TIME_COST: u32 = 200;
+const MIN_WAR_PARTY_MEMBERS: i32 = 2;
+const MAX_WAR_PARTY_MEMBERS: i32 = 3;
 
 pub fn player(ecs: &mut World, x: i32, y: i32) -> Entity {
     ecs
@@ -26,6 +32,7 @@ pub fn player(ecs: &mut World, x: i32, y: i32) -> Entity {
             dirty: true,
         })
         .with(Name { name: "Player".to_string() })
+        .with(Faction { name: PLAYER_FACTION.to_string() })
         .with(CombatStats {
             max_hp: 30,
             hp: 30,
@@ -33,35 +40,163 @@ pub fn player(ecs: &mut World, x: i32, y: i32) -> Entity {
             power: 5,
         })
         .with(TakesTurn { time_score: 0 })
+        .with(SpeedModifiers::default())
         .with(CanMove { time_cost: 20 })
-        .with(CanMelee { time_cost: 110 })
+        .with(CanMelee {
+            time_cost: 110,
+            damage: parse_dice("1d6"),
+        })
+        .with(CanUseItem { time_cost: 100 })
+        .with(Statistics::default())
+        .with(CanPickUp { time_cost: 40 })
+        .with(CanDrop { time_cost: 20 })
         .marked::<SimpleMarker<SerializeMe>>()
         .build()
 }
 
 
 pub fn random_monster(ecs: &mut World, x: i32, y: i32) {
-    if RNG.flip_coin() {
-        orc(ecs, x, y)
-    } else {
-        goblin(ecs, x, y)
+    match RNG.roll_die(7) {
+        1 | 2 => { orc(ecs, x, y); }
+        3 | 4 => { goblin(ecs, x, y); }
+        5 => { goblin_archer(ecs, x, y); }
+        6 => { goblin_shaman(ecs, x, y); }
+        _ => { goblin_outcast(ecs, x, y); }
     }
 }
 
 static mut COUNTER: i32 = 1;
 
-pub fn orc(ecs: &mut World, x: i32, y: i32) {
-    monster(ecs, x, y, rltk::to_cp437('o'), "Orc")
+pub fn orc(ecs: &mut World, x: i32, y: i32) -> Entity {
+    monster(ecs, Position { x, y }, rltk::to_cp437('o'), "Orc", ORC_FACTION, "1d8", loot_table(30, &[
+        ("Health Potion", 2),
+        ("Magic Missile Scroll", 1),
+    ]))
+}
+
+pub fn goblin(ecs: &mut World, x: i32, y: i32) -> Entity {
+    monster(ecs, Position { x, y }, rltk::to_cp437('g'), "Goblin", GOBLIN_FACTION, "1d4+1", loot_table(25, &[
+        ("Health Potion", 1),
+        ("Confusion Scroll", 1),
+    ]))
+}
+
+pub fn goblin_archer(ecs: &mut World, x: i32, y: i32) -> Entity {
+    let archer = monster(ecs, Position { x, y }, rltk::to_cp437('g'), "Goblin Archer", GOBLIN_FACTION, "1d3", loot_table(25, &[
+        ("Health Potion", 1),
+    ]));
+
+    let mut can_shoot = ecs.write_storage::<CanShoot>();
+    can_shoot
+        .insert(archer, CanShoot {
+            time_cost: 150,
+            range: 6,
+            preferred_distance: 4,
+            damage: parse_dice("1d6"),
+            damage_type: DamageType::Physical,
+            ammo: Some(8),
+            projectile_name: "arrow".to_string(),
+            projectile_glyph: rltk::to_cp437('∙'),
+            projectile_fg: RGB::named(rltk::BURLYWOOD),
+        })
+        .expect("Unable to insert ranged attack");
+
+    archer
+}
+
+pub fn goblin_outcast(ecs: &mut World, x: i32, y: i32) -> Entity {
+    let outcast = monster(ecs, Position { x, y }, rltk::to_cp437('g'), "Goblin Outcast", OUTCAST_FACTION, "1d4+1", loot_table(25, &[
+        ("Health Potion", 1),
+    ]));
+
+    let mut renderables = ecs.write_storage::<Renderable>();
+    if let Some(renderable) = renderables.get_mut(outcast) {
+        renderable.fg = RGB::named(rltk::LIGHT_GREEN);
+    }
+
+    let mut recruitables = ecs.write_storage::<Recruitable>();
+    recruitables
+        .insert(outcast, Recruitable)
+        .expect("Unable to insert recruitable");
+
+    outcast
+}
+
+pub fn goblin_chief(ecs: &mut World, x: i32, y: i32) -> Entity {
+    let chief = monster(ecs, Position { x, y }, rltk::to_cp437('G'), "Goblin Chief", GOBLIN_FACTION, "1d6+1", loot_table(60, &[
+        ("Health Potion", 1),
+        ("Fireball Scroll", 1),
+    ]));
+
+    let mut party_leaders = ecs.write_storage::<PartyLeader>();
+    party_leaders
+        .insert(chief, PartyLeader)
+        .expect("Unable to insert party leader");
+
+    chief
 }
 
-pub fn goblin(ecs: &mut World, x: i32, y: i32) {
-    monster(ecs, x, y, rltk::to_cp437('g'), "Goblin")
+pub fn goblin_war_party(ecs: &mut World, map: &Map, room: &Rect) {
+    let member_count = RNG.inclusive_range(MIN_WAR_PARTY_MEMBERS, MAX_WAR_PARTY_MEMBERS);
+    let mut spawn_points = get_spawn_points(map, member_count + 1, room).into_iter();
+
+    let leader_point = map.index_to_point2d(spawn_points.next().unwrap());
+    let leader = goblin_chief(ecs, leader_point.x, leader_point.y);
+
+    for idx in spawn_points {
+        let pt = map.index_to_point2d(idx);
+        let member = goblin(ecs, pt.x, pt.y);
+
+        let mut party_members = ecs.write_storage::<PartyMember>();
+        party_members
+            .insert(member, PartyMember { leader })
+            .expect("Unable to insert party member");
+    }
 }
 
-fn monster<S: ToString>(ecs: &mut World, x: i32, y: i32, glyph: u8, name: S) {
+pub fn goblin_shaman(ecs: &mut World, x: i32, y: i32) -> Entity {
+    let shaman = monster(ecs, Position { x, y }, rltk::to_cp437('g'), "Goblin Shaman", GOBLIN_FACTION, "1d4", loot_table(50, &[
+        ("Fireball Scroll", 2),
+        ("Confusion Scroll", 1),
+    ]));
+
+    let mut resistances = ecs.write_storage::<DamageResistance>();
+    resistances
+        .insert(shaman, DamageResistance { damage_types: vec![DamageType::Fire] })
+        .expect("Unable to insert resistance");
+
+    let mut vulnerabilities = ecs.write_storage::<DamageVulnerability>();
+    vulnerabilities
+        .insert(shaman, DamageVulnerability { damage_types: vec![DamageType::Cold] })
+        .expect("Unable to insert vulnerability");
+
+    let mut can_shoot = ecs.write_storage::<CanShoot>();
+    can_shoot
+        .insert(shaman, CanShoot {
+            time_cost: 200,
+            range: 5,
+            preferred_distance: 3,
+            damage: parse_dice("1d6+1"),
+            damage_type: DamageType::Fire,
+            ammo: None,
+            projectile_name: "firebolt".to_string(),
+            projectile_glyph: rltk::to_cp437('*'),
+            projectile_fg: RGB::named(rltk::ORANGE),
+        })
+        .expect("Unable to insert ranged attack");
+
+    shaman
+}
+
+fn monster<S: ToString>(ecs: &mut World, position: Position, glyph: u8, name: S, faction: &str, damage: &str, loot_table: LootTable) -> Entity {
+    let awareness_state = match RNG.roll_die(SLEEP_CHANCE) {
+        1 => AwarenessState::Asleep,
+        _ => AwarenessState::Wandering,
+    };
+
     let monster_builder = ecs
         .create_entity()
-        .with(Position { x, y })
+        .with(position)
         .with(Renderable {
             glyph,
             fg: RGB::named(rltk::RED),
@@ -69,6 +204,7 @@ fn monster<S: ToString>(ecs: &mut World, x: i32, y: i32, glyph: u8, name: S) {
             render_order: 1,
         })
         .with(Monster)
+        .with(Faction { name: faction.to_string() })
         .with(Viewshed {
             visible_tiles: Vec::new(),
             range: 8,
@@ -81,27 +217,149 @@ fn monster<S: ToString>(ecs: &mut World, x: i32, y: i32, glyph: u8, name: S) {
             defense: 1,
             power: 4,
         })
+        .with(loot_table)
+        .with(Morale::new(MONSTER_MORALE))
+        .with(Awareness { state: awareness_state })
+        .with(ai_profile(&name.to_string()))
         .with(TakesTurn { time_score: 0 })
+        .with(SpeedModifiers::default())
         .with(CanMove { time_cost: 30 })
-        .with(CanMelee { time_cost: 180 })
+        .with(CanMelee {
+            time_cost: 180,
+            damage: parse_dice(damage),
+        })
+        .with(CanUseItem { time_cost: 150 })
+        .with(CanPickUp { time_cost: 60 })
+        .with(CanDrop { time_cost: 30 })
         .marked::<SimpleMarker<SerializeMe>>();
 
 
     if DEBUG {
         unsafe {
-            monster_builder.with(Name { name: format!("{} #{}", name.to_string(), COUNTER) }).build();
-            COUNTER += 1
-        };
+            let monster = monster_builder.with(Name { name: format!("{} #{}", name.to_string(), COUNTER) }).build();
+            COUNTER += 1;
+            monster
+        }
     } else {
-        monster_builder.with(Name { name: name.to_string() }).build();
+        monster_builder.with(Name { name: name.to_string() }).build()
+    }
+}
+
+type ActionRow = (UtilityAction, f32, &'static [Consideration]);
+
+const DEFAULT_AI_ACTIONS: &[ActionRow] = &[
+    (UtilityAction::Heal, 6., &[Consideration::HealthBelow(0.5)]),
+    (UtilityAction::Flee, 5., &[Consideration::MoraleBroken]),
+    (UtilityAction::Scatter, 4.5, &[]),
+    (UtilityAction::Melee, 3., &[]),
+    (UtilityAction::Throw, 2.5, &[Consideration::MoraleHolding]),
+    (UtilityAction::PickUp, 2., &[]),
+    (UtilityAction::Approach, 1.5, &[Consideration::MoraleHolding]),
+    (UtilityAction::Search, 1., &[Consideration::MoraleHolding]),
+    (UtilityAction::Follow, 0.8, &[]),
+    (UtilityAction::Wander, 0.6, &[Consideration::Random]),
+    (UtilityAction::Wait, 0.1, &[]),
+];
+
+const RANGED_AI_ACTIONS: &[ActionRow] = &[
+    (UtilityAction::Melee, 2., &[]),
+    (UtilityAction::Shoot, 2.8, &[Consideration::MoraleHolding]),
+    (UtilityAction::Retreat, 3.5, &[Consideration::MoraleHolding, Consideration::InsidePreferredDistance]),
+];
+
+/// Per monster type: the actions that replace or add to the default ones, and the actions it never takes.
+const AI_PROFILE_TABLE: &[(&str, &[ActionRow], &[UtilityAction])] = &[
+    ("Goblin Archer", RANGED_AI_ACTIONS, &[]),
+    ("Goblin Shaman", RANGED_AI_ACTIONS, &[]),
+    ("Goblin Chief", &[(UtilityAction::Melee, 3.5, &[])], &[]),
+    ("Goblin Outcast", &[], &[UtilityAction::PickUp]),
+];
+
+/// Builds the profile of a monster type from the table; unknown types get the default actions.
+pub fn ai_profile(monster_name: &str) -> AiProfile {
+    let (actions, removed_actions) = AI_PROFILE_TABLE
+        .iter()
+        .find(|(name, _, _)| *name == monster_name)
+        .map_or((&[][..], &[][..]), |(_, actions, removed_actions)| (*actions, *removed_actions));
+
+    let profile = DEFAULT_AI_ACTIONS
+        .iter()
+        .chain(actions.iter())
+        .fold(AiProfile { actions: Vec::new() }, |profile, (action, weight, considerations)| {
+            profile.with_action(*action, *weight, considerations)
+        });
+
+    removed_actions
+        .iter()
+        .fold(profile, |profile, action| profile.without_action(*action))
+}
+
+fn loot_table(drop_chance: i32, drops: &[(&str, i32)]) -> LootTable {
+    LootTable {
+        drop_chance,
+        drops: drops
+            .iter()
+            .map(|(item, weight)| LootDrop { item: item.to_string(), weight: *weight })
+            .collect(),
+    }
+}
+
+pub fn roll_loot_table(loot_table: &LootTable) -> Option<String> {
+    if RNG.inclusive_range(1, 100) > loot_table.drop_chance {
+        return None;
+    }
+
+    let total_weight: i32 = loot_table.drops.iter().map(|drop| drop.weight).sum();
+    if total_weight <= 0 {
+        return None;
+    }
+
+    let mut roll = RNG.inclusive_range(1, total_weight);
+    for drop in loot_table.drops.iter() {
+        if roll <= drop.weight {
+            return Some(drop.item.to_string());
+        }
+        roll -= drop.weight;
+    }
+
+    None
+}
+
+pub fn corpse<S: ToString>(ecs: &mut World, x: i32, y: i32, name: S) {
+    ecs
+        .create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: rltk::to_cp437('%'),
+            fg: RGB::named(rltk::DARK_RED),
+            bg: RGB::named(rltk::BLACK),
+            render_order: 3,
+        })
+        .with(Name { name: format!("{} Corpse", name.to_string()) })
+        .with(Corpse)
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+pub fn named_item(ecs: &mut World, name: &str, x: i32, y: i32) {
+    match name {
+        "Health Potion" => health_potion(ecs, x, y),
+        "Fireball Scroll" => fireball_scroll(ecs, x, y),
+        "Confusion Scroll" => confusion_scroll(ecs, x, y),
+        "Magic Missile Scroll" => magic_missile_scroll(ecs, x, y),
+        "Speed Potion" => speed_potion(ecs, x, y),
+        "Slowness Scroll" => slowness_scroll(ecs, x, y),
+        _ => console_log(format!("Unknown item: {}", name)),
     }
 }
 
 pub fn random_item(ecs: &mut World, x: i32, y: i32) {
-    match RNG.roll_die(4) {
+    match RNG.roll_die(6) {
         1 => { health_potion(ecs, x, y) }
         2 => { fireball_scroll(ecs, x, y) }
         3 => { confusion_scroll(ecs, x, y) }
+        4 => { speed_potion(ecs, x, y) }
+        5 => { slowness_scroll(ecs, x, y) }
         _ => { magic_missile_scroll(ecs, x, y) }
     }
 }
@@ -128,7 +386,7 @@ pub fn health_potion_base(ecs: &mut World) -> EntityBuilder {
             render_order: 2,
         })
         .with(Name { name: "Health Potion".to_string() })
-        .with(Item)
+        .with(Item { kind: ItemKind::Potion })
         .with(Consumable)
         .with(ProvidesHealing {
             heal_amount: 8
@@ -136,6 +394,52 @@ pub fn health_potion_base(ecs: &mut World) -> EntityBuilder {
         .marked::<SimpleMarker<SerializeMe>>()
 }
 
+pub fn speed_potion(ecs: &mut World, x: i32, y: i32) {
+    ecs
+        .create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: rltk::to_cp437('¡'),
+            fg: RGB::named(rltk::CYAN),
+            bg: RGB::named(rltk::BLACK),
+            render_order: 2,
+        })
+        .with(Name { name: "Speed Potion".to_string() })
+        .with(Item { kind: ItemKind::Potion })
+        .with(Consumable)
+        .with(ModifiesSpeed {
+            source: SpeedSource::Haste,
+            multiplier: 0.5,
+            turns: 10,
+        })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
+pub fn slowness_scroll(ecs: &mut World, x: i32, y: i32) {
+    ecs
+        .create_entity()
+        .with(Position { x, y })
+        .with(Renderable {
+            glyph: rltk::to_cp437(')'),
+            fg: RGB::named(rltk::SADDLEBROWN),
+            bg: RGB::named(rltk::BLACK),
+            render_order: 2,
+        })
+        .with(Name { name: "Slowness Scroll".to_string() })
+        .with(Item { kind: ItemKind::Scroll })
+        .with(UseTimeCost { time_cost: SCROLL_USE_TIME_COST })
+        .with(Consumable)
+        .with(Ranged { range: 6 })
+        .with(ModifiesSpeed {
+            source: SpeedSource::Slow,
+            multiplier: 2.,
+            turns: 6,
+        })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+}
+
 pub fn magic_missile_scroll(ecs: &mut World, x: i32, y: i32) {
     magic_missile_scroll_base(ecs)
         .with(Position { x, y })
@@ -152,10 +456,14 @@ pub fn magic_missile_scroll_base(ecs: &mut World) -> EntityBuilder {
             render_order: 2,
         })
         .with(Name { name: "Magic Missile Scroll".to_string() })
-        .with(Item)
+        .with(Item { kind: ItemKind::Scroll })
+        .with(UseTimeCost { time_cost: SCROLL_USE_TIME_COST })
         .with(Consumable)
         .with(Ranged { range: 6 })
-        .with(InflictsDamage { damage: 8 })
+        .with(InflictsDamage {
+            damage: 8,
+            damage_type: DamageType::Arcane,
+        })
         .marked::<SimpleMarker<SerializeMe>>()
 }
 
@@ -186,7 +494,8 @@ fn confusion_scroll_base(ecs: &mut World) -> EntityBuilder {
             render_order: 2,
         })
         .with(Name { name: "Confusion Scroll".to_string() })
-        .with(Item)
+        .with(Item { kind: ItemKind::Scroll })
+        .with(UseTimeCost { time_cost: SCROLL_USE_TIME_COST })
         .with(Consumable)
         .with(Ranged { range: 6 })
         .with(Confusion { turns: 4 })
@@ -217,10 +526,14 @@ fn fireball_scroll_base(ecs: &mut World) -> EntityBuilder {
             render_order: 2,
         })
         .with(Name { name: "Fireball Scroll".to_string() })
-        .with(Item)
+        .with(Item { kind: ItemKind::Scroll })
+        .with(UseTimeCost { time_cost: SCROLL_USE_TIME_COST })
         .with(Consumable)
         .with(Ranged { range: 6 })
-        .with(InflictsDamage { damage: 20 })
+        .with(InflictsDamage {
+            damage: 20,
+            damage_type: DamageType::Fire,
+        })
         .with(AreaOfEffect { radius: 3 })
         .marked::<SimpleMarker<SerializeMe>>()
 }
@@ -251,12 +564,17 @@ fn spawn_room(ecs: &mut World, map: &Map, room: &Rect) {
     let monster_count = RNG.inclusive_range(0, MAX_MONSTERS + DROP_OFFSET) - DROP_OFFSET;
     let item_count = RNG.inclusive_range(0, MAX_ITEMS + DROP_OFFSET) - DROP_OFFSET;
 
-    let monster_spawn_points = get_spawn_points(map, monster_count, room);
     let item_spawn_points = get_spawn_points(map, item_count, room);
 
-    for idx in monster_spawn_points {
-        let pt = map.index_to_point2d(idx);
-        random_monster(ecs, pt.x, pt.y);
+    if RNG.roll_die(WAR_PARTY_CHANCE) == 1 {
+        goblin_war_party(ecs, map, room);
+    } else {
+        let monster_spawn_points = get_spawn_points(map, monster_count, room);
+
+        for idx in monster_spawn_points {
+            let pt = map.index_to_point2d(idx);
+            random_monster(ecs, pt.x, pt.y);
+        }
     }
 
     for idx in item_spawn_points {
@@ -287,10 +605,10 @@ pub fn spawn_global_turn(ecs: &mut World) {
     ecs
         .create_entity()
         .with(Name { name: "Global Turn".to_string() })
-        .with(GlobalTurn)
+        .with(GlobalTurn { turn: 0 })
         .with(TakesTurn { time_score: 0 })
         .marked::<SimpleMarker<SerializeMe>>()
         .build();
 
-    ecs.insert(GlobalTurnTimeScore { time_score: 0 });
+    ecs.insert(GlobalTurnTimeScore { time_score: 0, turn: 0 });
 }
\ No newline at end of file
@@ -2,7 +2,7 @@ use rltk::{GameState, render_draw_buffer, Rltk};
 use specs::prelude::*;
 use specs::WorldExt;
 
-use crate::{console_log, Context, cull_dead_particles, DamageSystem, decide_turn, delete_the_dead, GlobalTurnSystem, gui, ItemCollectionSystem, ItemDropSystem, ItemMenuResult, ItemUseSystem, load_game, MainMenuSelection, MapIndexingSystem, MeleeCombatSystem, MonsterAI, MovementSystem, ParticleSpawnSystem, player_input, Ranged, RangedTargetDrawerSettings, RangedTargetResult, render_camera, save_game, VisibilitySystem, WaitSystem, WantsToDrop, WantsToUseItem};
+use crate::{ActionSystem, CharacterSheetResult, console_log, Context, cull_dead_particles, DamageSystem, DeathLogSystem, decide_turn, delete_the_dead, GameEvents, GlobalTurnSystem, gui, HelpResult, HistoryResult, ItemCollectionSystem, ItemDropSystem, ItemMenuResult, ItemUseSystem, load_game, MainMenuSelection, MapIndexingSystem, MeleeCombatSystem, MonsterAI, MovementSystem, NoiseSystem, ParticleSpawnSystem, player_input, Ranged, RangedCombatSystem, RangedTargetDrawerSettings, RangedTargetResult, RecruitSystem, render_camera, save_game, SpeedSystem, StatisticsSystem, TargetingCursor, VisibilitySystem, WaitSystem, WantsToDrop, WantsToUseItem};
 
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum RunState {
@@ -14,6 +14,9 @@ pub enum RunState {
     ShowInventory,
     ShowDropItem,
     ShowTargeting { range: i32, item: Entity, radius: Option<i32> },
+    ShowHistory,
+    ShowHelp,
+    ShowCharacterSheet,
     MainMenu { menu_selection: MainMenuSelection },
     SaveGame,
 }
@@ -39,6 +42,7 @@ pub struct RunStateHolder {
 
 pub struct GlobalTurnTimeScore {
     pub time_score: u32,
+    pub turn: u32,
 }
 
 impl State {
@@ -105,6 +109,7 @@ impl GameState for State {
                         let player_entity = self.ecs.read_resource::<Entity>();
                         match self.ecs.read_storage::<Ranged>().get(selected_item) {
                             Some(ranged) => {
+                                self.ecs.write_resource::<TargetingCursor>().reset();
                                 new_run_state = RunState::ShowTargeting {
                                     item: selected_item,
                                     range: ranged.range,
@@ -162,6 +167,24 @@ impl GameState for State {
                     }
                 }
             }
+            RunState::ShowHistory => {
+                match gui::show_history(self, context) {
+                    HistoryResult::Close => new_run_state = RunState::AwaitingInput,
+                    HistoryResult::NoResponse => {}
+                }
+            }
+            RunState::ShowHelp => {
+                match gui::show_help(self, context) {
+                    HelpResult::Close => new_run_state = RunState::AwaitingInput,
+                    HelpResult::NoResponse => {}
+                }
+            }
+            RunState::ShowCharacterSheet => {
+                match gui::show_character_sheet(self, context) {
+                    CharacterSheetResult::Close => new_run_state = RunState::AwaitingInput,
+                    CharacterSheetResult::NoResponse => {}
+                }
+            }
             RunState::MainMenu { .. } => {
                 let result = gui::main_menu(self, context);
                 match result {
@@ -202,14 +225,21 @@ impl SysRunner {
             .with(MapIndexingSystem, MapIndexingSystem::NAME, &[])
             .with(VisibilitySystem, "vis", &[MapIndexingSystem::NAME])
             .with(GlobalTurnSystem, "global", &[MapIndexingSystem::NAME])
-            .with(MonsterAI, MonsterAI::NAME, &[MapIndexingSystem::NAME])
+            .with(SpeedSystem, SpeedSystem::NAME, &["global"])
+            .with(MonsterAI, MonsterAI::NAME, &[MapIndexingSystem::NAME, SpeedSystem::NAME])
             .with(MovementSystem, MovementSystem::NAME, &[MonsterAI::NAME])
             .with(MeleeCombatSystem, "melee_combat", &[MonsterAI::NAME])
+            .with(RangedCombatSystem, RangedCombatSystem::NAME, &[MonsterAI::NAME])
             .with(WaitSystem, "wait", &[MonsterAI::NAME])
+            .with(RecruitSystem, RecruitSystem::NAME, &[MonsterAI::NAME])
             .with(ItemCollectionSystem, "pick_up", &[MonsterAI::NAME])
             .with(ItemUseSystem, "use_item", &[MonsterAI::NAME])
             .with(ItemDropSystem, "drop", &[MonsterAI::NAME])
-            .with(DamageSystem, "damage", &["melee_combat", "use_item"])
+            .with(ActionSystem, ActionSystem::NAME, &[MovementSystem::NAME, "melee_combat", RangedCombatSystem::NAME, "wait", RecruitSystem::NAME, "pick_up", "use_item", "drop"])
+            .with(DamageSystem, "damage", &["melee_combat", RangedCombatSystem::NAME, "use_item", ActionSystem::NAME])
+            .with(NoiseSystem, NoiseSystem::NAME, &[MovementSystem::NAME, "melee_combat", "use_item", "damage"])
+            .with(StatisticsSystem, StatisticsSystem::NAME, &["damage", ActionSystem::NAME])
+            .with(DeathLogSystem, DeathLogSystem::NAME, &["damage"])
             .with(ParticleSpawnSystem, "particle", &["damage"])
             .build();
 
@@ -217,6 +247,7 @@ impl SysRunner {
     }
 
     pub fn run(&mut self, ecs: &mut World) {
+        ecs.write_resource::<GameEvents>().clear();
         self.dispatcher.dispatch(ecs);
         ecs.maintain();
     }
@@ -0,0 +1,123 @@
+extern crate specs;
+
+use specs::prelude::*;
+
+use crate::{console_log, GameEvent, GameEvents, GameLog, LogMessage, Name, SpeedModifiers, TakesTurn};
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum ActionKind {
+    Move,
+    Swap,
+    Melee,
+    Shoot,
+    Wait,
+    UseItem,
+    PickUp,
+    Drop,
+    Talk,
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum ActionResult {
+    Success,
+    Failed,
+}
+
+/// One action taken by an actor, with the log messages and events it produced.
+/// The action system charges its time, writes its messages to the log and emits its events.
+pub struct ActionRecord {
+    pub actor: Entity,
+    pub kind: ActionKind,
+    pub time_cost: u32,
+    pub result: ActionResult,
+    pub messages: Vec<LogMessage>,
+    pub events: Vec<GameEvent>,
+}
+
+impl ActionRecord {
+    pub fn log(&mut self, message: LogMessage) -> &mut ActionRecord {
+        self.messages.push(message);
+        self
+    }
+
+    pub fn event(&mut self, event: GameEvent) -> &mut ActionRecord {
+        self.events.push(event);
+        self
+    }
+}
+
+pub struct ActionRecorder {
+    records: Vec<ActionRecord>
+}
+
+impl ActionRecorder {
+    pub fn new() -> ActionRecorder {
+        ActionRecorder { records: Vec::new() }
+    }
+
+    pub fn record(&mut self, actor: Entity, kind: ActionKind, time_cost: u32, result: ActionResult) -> &mut ActionRecord {
+        self.records.push(ActionRecord {
+            actor,
+            kind,
+            time_cost,
+            result,
+            messages: Vec::new(),
+            events: Vec::new(),
+        });
+        self.records.last_mut().unwrap()
+    }
+}
+
+impl Default for ActionRecorder {
+    fn default() -> Self {
+        ActionRecorder::new()
+    }
+}
+
+pub struct ActionSystem;
+
+impl ActionSystem {
+    pub const NAME: &'static str = "action";
+}
+
+impl<'a> System<'a> for ActionSystem {
+    type SystemData = (
+        WriteExpect<'a, ActionRecorder>,
+        WriteStorage<'a, TakesTurn>,
+        ReadStorage<'a, SpeedModifiers>,
+        ReadStorage<'a, Name>,
+        WriteExpect<'a, GameLog>,
+        WriteExpect<'a, GameEvents>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            mut action_recorder,
+            mut takes_turn,
+            speed_modifiers,
+            names,
+            mut game_log,
+            mut game_events,
+        ) = data;
+
+        for record in action_recorder.records.drain(..) {
+            let time_cost = SpeedModifiers::get_time_cost(&speed_modifiers, record.actor, record.time_cost);
+
+            if let Some(takes_turn) = takes_turn.get_mut(record.actor) {
+                takes_turn.time_score += time_cost;
+            }
+
+            let name = names.get(record.actor).map_or("Something", |name| &name.name);
+            console_log(format!("           {} {:?} {:?} (cost {})", name, record.kind, record.result, time_cost));
+
+            for message in record.messages {
+                game_log.add(message);
+            }
+
+            game_events.push(GameEvent::ActionTaken { actor: record.actor, kind: record.kind, result: record.result, time_cost });
+            for event in record.events {
+                game_events.push(event);
+            }
+        }
+    }
+}
@@ -3,10 +3,13 @@ extern crate specs;
 use rltk::Point;
 use specs::prelude::*;
 
-use crate::{CombatStats, GameLog, MEDIUM_LIFETIME, Name, ParticleBuilder, Player, Position, RunStateHolder, SuffersDamage};
+use crate::{Awareness, AwarenessState, CombatStats, corpse, DamageImmunity, DamageResistance, DamageVulnerability, Faction, FactionTable, GameEvent, GameEvents, GameLog, InBackpack, IsVisible, KilledBy, LogCategory, LogMessage, LootTable, MEDIUM_LIFETIME, Morale, Name, named_item, ParticleBuilder, PartyLeader, PartyMember, Player, Position, Reaction, roll_loot_table, Scattering, SuffersDamage, Viewshed};
 
 pub struct DamageSystem;
 
+const MORALE_LOSS_PER_FULL_HEALTH: i32 = 10;
+const ALLY_DEATH_MORALE_LOSS: i32 = 3;
+
 impl<'a> System<'a> for DamageSystem {
     type SystemData = (
         Entities<'a>,
@@ -14,6 +17,15 @@ impl<'a> System<'a> for DamageSystem {
         WriteStorage<'a, SuffersDamage>,
         WriteExpect<'a, ParticleBuilder>,
         ReadStorage<'a, Position>,
+        WriteExpect<'a, GameLog>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, DamageResistance>,
+        ReadStorage<'a, DamageImmunity>,
+        ReadStorage<'a, DamageVulnerability>,
+        WriteStorage<'a, KilledBy>,
+        WriteStorage<'a, Morale>,
+        WriteStorage<'a, Awareness>,
+        WriteExpect<'a, GameEvents>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -23,14 +35,83 @@ impl<'a> System<'a> for DamageSystem {
             mut suffers_damage,
             mut particle_builder,
             positions,
+            mut game_log,
+            names,
+            resistances,
+            immunities,
+            vulnerabilities,
+            mut killed_by,
+            mut morales,
+            mut awarenesses,
+            mut game_events,
         ) = data;
 
         for (entity, mut combat_stat, suffer_damage) in (&entities, &mut combat_stats, &suffers_damage).join() {
-            if suffer_damage.amount == 0 {
+            let victim_name = names.get(entity).map_or("Something", |name| &name.name);
+            let mut total_amount = 0;
+
+            for instance in suffer_damage.instances.iter() {
+                let damage_type = instance.damage_type;
+
+                let is_immune = immunities
+                    .get(entity)
+                    .is_some_and(|immunity| immunity.damage_types.contains(&damage_type));
+                let is_resistant = resistances
+                    .get(entity)
+                    .is_some_and(|resistance| resistance.damage_types.contains(&damage_type));
+                let is_vulnerable = vulnerabilities
+                    .get(entity)
+                    .is_some_and(|vulnerability| vulnerability.damage_types.contains(&damage_type));
+
+                let amount = if is_immune {
+                    game_log.add(LogMessage::new(LogCategory::Combat)
+                        .actor(victim_name)
+                        .text(format!(" is immune to {} damage.", damage_type)));
+                    0
+                } else if is_resistant && !is_vulnerable {
+                    game_log.add(LogMessage::new(LogCategory::Combat)
+                        .actor(victim_name)
+                        .text(format!(" resists the {} damage.", damage_type)));
+                    instance.amount / 2
+                } else if is_vulnerable && !is_resistant {
+                    game_log.add(LogMessage::new(LogCategory::Combat)
+                        .actor(victim_name)
+                        .text(format!(" is vulnerable to {} damage!", damage_type)));
+                    instance.amount * 2
+                } else {
+                    instance.amount
+                };
+
+                if amount == 0 {
+                    continue;
+                }
+
+                let was_alive = combat_stat.hp > 0;
+                combat_stat.hp -= amount;
+                total_amount += amount;
+
+                game_events.push(GameEvent::Damaged { victim: entity, source: instance.source, amount, damage_type });
+
+                if was_alive && combat_stat.hp <= 0 {
+                    killed_by
+                        .insert(entity, KilledBy { killer: instance.source })
+                        .expect("Unable to insert kill credit");
+                    game_events.push(GameEvent::Died { victim: entity, killer: instance.source });
+                }
+            }
+
+            if total_amount == 0 {
                 continue;
             }
 
-            combat_stat.hp -= suffer_damage.amount;
+            if let Some(morale) = morales.get_mut(entity) {
+                let max_hp = i32::max(1, combat_stat.max_hp);
+                morale.lower((total_amount * MORALE_LOSS_PER_FULL_HEALTH + max_hp - 1) / max_hp);
+            }
+
+            if let Some(awareness) = awarenesses.get_mut(entity) {
+                awareness.state = AwarenessState::Hunting;
+            }
 
             if let Some(position) = positions.get(entity) {
                 particle_builder.request_aura(
@@ -48,39 +129,166 @@ impl<'a> System<'a> for DamageSystem {
 
 
 pub fn delete_the_dead(ecs: &mut World) {
-    let mut dead: Vec<Entity> = Vec::new();
-
-    {
+    let dead = {
         let combat_stats = ecs.read_storage::<CombatStats>();
-        let names = ecs.read_storage::<Name>();
         let entities = ecs.entities();
         let players = ecs.read_storage::<Player>();
-        let mut game_log = ecs.write_resource::<GameLog>();
-        let run_state_holder = ecs.read_resource::<RunStateHolder>();
-
-        for (entity, stats) in (&entities, &combat_stats).join() {
-            if stats.hp <= 0 {
-                let player_or_null = players.get(entity);
-
-                match player_or_null {
-                    None => {
-                        let victim_name = names.get(entity);
-                        if let Some(victim_name) = victim_name {
-                            game_log.add(format!("{} is dead", victim_name.name));
-                        }
-                        dead.push(entity);
-                    }
-                    Some(_player) => {
-                        if run_state_holder.run_state.is_turn() {
-                            game_log.add("You are dead".to_string());
-                        }
-                    }
-                }
-            }
-        }
-    }
+
+        (&entities, &combat_stats, !&players)
+            .join()
+            .filter(|(_, stats, _)| stats.hp <= 0)
+            .map(|(entity, _, _)| entity)
+            .collect::<Vec<Entity>>()
+    };
 
     for victim in dead {
+        demoralize_witnesses(ecs, victim);
+        disband_party(ecs, victim);
+        leave_remains(ecs, victim);
         ecs.delete_entity(victim).expect("Unable to delete");
     }
-}
\ No newline at end of file
+}
+
+fn demoralize_witnesses(ecs: &mut World, victim: Entity) {
+    let victim_position = match ecs.read_storage::<Position>().get(victim) {
+        None => return,
+        Some(position) => Point::new(position.x, position.y),
+    };
+
+    let entities = ecs.entities();
+    let viewsheds = ecs.read_storage::<Viewshed>();
+    let factions = ecs.read_storage::<Faction>();
+    let faction_table = ecs.fetch::<FactionTable>();
+    let mut morales = ecs.write_storage::<Morale>();
+
+    for (witness, viewshed, morale) in (&entities, &viewsheds, &mut morales).join() {
+        let is_ally = faction_table.get_entity_reaction(&factions, witness, victim) == Reaction::Friendly;
+
+        if witness != victim && is_ally && viewshed.visible_tiles.contains(&victim_position) {
+            morale.lower(ALLY_DEATH_MORALE_LOSS);
+        }
+    }
+}
+
+fn disband_party(ecs: &mut World, leader: Entity) {
+    const SCATTER_TURNS: i32 = 6;
+
+    if ecs.read_storage::<PartyLeader>().get(leader).is_none() {
+        return;
+    }
+
+    let leader_position = match ecs.read_storage::<Position>().get(leader) {
+        None => return,
+        Some(position) => Point::new(position.x, position.y),
+    };
+
+    let entities = ecs.entities();
+    let mut party_members = ecs.write_storage::<PartyMember>();
+    let mut scatterings = ecs.write_storage::<Scattering>();
+    let is_visible = ecs.read_storage::<IsVisible>();
+
+    let members = (&entities, &party_members)
+        .join()
+        .filter(|(_, party_member)| party_member.leader == leader)
+        .map(|(member, _)| member)
+        .collect::<Vec<Entity>>();
+
+    for member in members.iter() {
+        party_members.remove(*member);
+        scatterings
+            .insert(*member, Scattering { from: leader_position, turns: SCATTER_TURNS })
+            .expect("Unable to insert scattering");
+    }
+
+    if members.iter().any(|member| is_visible.get(*member).is_some()) {
+        ecs.write_resource::<GameLog>().add(LogMessage::new(LogCategory::Status).text("The war party ").colored("scatters", rltk::RGB::named(rltk::YELLOW)).text("!"));
+    }
+}
+
+fn leave_remains(ecs: &mut World, victim: Entity) {
+    let position = match ecs.read_storage::<Position>().get(victim) {
+        None => return,
+        Some(position) => *position,
+    };
+
+    {
+        let entities = ecs.entities();
+        let mut in_backpacks = ecs.write_storage::<InBackpack>();
+        let mut positions = ecs.write_storage::<Position>();
+
+        let carried_items = (&entities, &in_backpacks)
+            .join()
+            .filter(|(_, in_backpack)| in_backpack.owner == victim)
+            .map(|(item, _)| item)
+            .collect::<Vec<Entity>>();
+
+        for item in carried_items {
+            in_backpacks.remove(item);
+            positions.insert(item, position).expect("Unable to insert position");
+        }
+    }
+
+    let loot = ecs
+        .read_storage::<LootTable>()
+        .get(victim)
+        .and_then(roll_loot_table);
+
+    if let Some(loot) = loot {
+        named_item(ecs, &loot, position.x, position.y);
+    }
+
+    let victim_name = ecs
+        .read_storage::<Name>()
+        .get(victim)
+        .map(|name| name.name.to_string());
+
+    if let Some(victim_name) = victim_name {
+        corpse(ecs, position.x, position.y, victim_name);
+    }
+}
+#[cfg(test)]
+mod tests {
+    use specs::prelude::*;
+
+    use crate::{CombatStats, DamageResistance, DamageSystem, DamageType, DamageVulnerability, GameEvent, GameEvents, GameLog, KilledBy, LogCategory, LogMessage, ParticleBuilder, SuffersDamage};
+
+    #[test]
+    fn applies_every_hit_and_credits_the_killing_blow() {
+        let mut world = World::new();
+        world.insert(ParticleBuilder::new());
+        world.insert(GameLog::new_with_first_log(LogMessage::new(LogCategory::System).text("Test")));
+        world.insert(GameEvents::new());
+        System::setup(&mut DamageSystem, &mut world);
+
+        let first_attacker = world.create_entity().build();
+        let second_attacker = world.create_entity().build();
+        let victim = world
+            .create_entity()
+            .with(CombatStats { max_hp: 10, hp: 10, defense: 0, power: 0 })
+            .with(DamageResistance { damage_types: vec![DamageType::Physical] })
+            .with(DamageVulnerability { damage_types: vec![DamageType::Fire] })
+            .build();
+
+        {
+            let mut suffers_damage = world.write_storage::<SuffersDamage>();
+            SuffersDamage::new_damage(&mut suffers_damage, victim, 4, DamageType::Physical, first_attacker);
+            SuffersDamage::new_damage(&mut suffers_damage, victim, 5, DamageType::Fire, second_attacker);
+        }
+
+        DamageSystem.run_now(&world);
+
+        assert_eq!(world.read_storage::<CombatStats>().get(victim).unwrap().hp, 10 - 2 - 10);
+        assert_eq!(world.read_storage::<KilledBy>().get(victim).unwrap().killer, second_attacker);
+        assert!(world.read_storage::<SuffersDamage>().get(victim).is_none());
+
+        let deaths = world
+            .fetch::<GameEvents>()
+            .iter()
+            .filter_map(|event| match *event {
+                GameEvent::Died { victim, killer } => Some((victim, killer)),
+                _ => None,
+            })
+            .collect::<Vec<(Entity, Entity)>>();
+        assert_eq!(deaths, vec![(victim, second_attacker)]);
+    }
+}
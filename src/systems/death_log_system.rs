@@ -0,0 +1,68 @@
+extern crate specs;
+
+use specs::prelude::*;
+
+use crate::{GameEvent, GameEvents, GameLog, LogCategory, LogMessage, Name};
+
+/// Writes a log line for every death on the event queue.
+pub struct DeathLogSystem;
+
+impl DeathLogSystem {
+    pub const NAME: &'static str = "death_log";
+}
+
+impl<'a> System<'a> for DeathLogSystem {
+    type SystemData = (
+        ReadExpect<'a, GameEvents>,
+        WriteExpect<'a, GameLog>,
+        ReadStorage<'a, Name>,
+        ReadExpect<'a, Entity>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            game_events,
+            mut game_log,
+            names,
+            player_entity,
+        ) = data;
+
+        for event in game_events.iter() {
+            let (victim, killer) = match *event {
+                GameEvent::Died { victim, killer } => (victim, killer),
+                _ => continue,
+            };
+
+            let killer_name = names.get(killer).map(|name| &name.name);
+
+            if victim == *player_entity {
+                match killer_name {
+                    Some(killer_name) if killer != victim => game_log.add(LogMessage::new(LogCategory::Combat)
+                        .text("You were killed by ")
+                        .actor(killer_name)
+                        .important()),
+                    _ => game_log.add(LogMessage::new(LogCategory::Combat).text("You are dead").important()),
+                }
+                continue;
+            }
+
+            let victim_name = match names.get(victim) {
+                None => continue,
+                Some(name) => &name.name,
+            };
+
+            match killer_name {
+                _ if killer == *player_entity => {
+                    game_log.add(LogMessage::new(LogCategory::Combat).text("You killed ").actor(victim_name));
+                }
+                Some(killer_name) => {
+                    game_log.add(LogMessage::new(LogCategory::Combat)
+                        .actor(victim_name)
+                        .text(" is killed by ")
+                        .actor(killer_name));
+                }
+                None => game_log.add(LogMessage::new(LogCategory::Combat).actor(victim_name).text(" is dead")),
+            }
+        }
+    }
+}
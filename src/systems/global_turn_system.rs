@@ -3,7 +3,7 @@ extern crate specs;
 
 use specs::prelude::*;
 
-use crate::{Confusion, console_log, GlobalTurn, GlobalTurnTimeScore, TakesTurn, WantsToTakeTurn};
+use crate::{Confusion, console_log, GameLog, GlobalTurn, GlobalTurnTimeScore, SpeedModifiers, TakesTurn, WantsToTakeTurn};
 
 pub struct GlobalTurnSystem;
 
@@ -11,20 +11,24 @@ impl<'a> System<'a> for GlobalTurnSystem {
     type SystemData = (
         WriteStorage<'a, TakesTurn>,
         ReadStorage<'a, WantsToTakeTurn>,
-        ReadStorage<'a, GlobalTurn>,
+        WriteStorage<'a, GlobalTurn>,
         WriteExpect<'a, GlobalTurnTimeScore>,
         WriteStorage<'a, Confusion>,
-        Entities<'a>
+        Entities<'a>,
+        WriteStorage<'a, SpeedModifiers>,
+        WriteExpect<'a, GameLog>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
         let (
             mut takes_turn,
             wants_to_take_turn,
-            global_turn,
+            mut global_turn,
             mut global_turn_time_score,
             mut confusions,
             entities,
+            mut speed_modifiers,
+            mut game_log,
         ) = data;
 
         const TIME_SCORE_LIMIT: u32 = 1000;
@@ -41,17 +45,21 @@ impl<'a> System<'a> for GlobalTurnSystem {
 
         let mut turn_taken = false;
         {
-            for (mut global_takes_turn, _, _) in (&mut takes_turn, &wants_to_take_turn, &global_turn).join() {
+            for (mut global_takes_turn, _, global_turn) in (&mut takes_turn, &wants_to_take_turn, &mut global_turn).join() {
                 turn_taken = true;
                 global_takes_turn.time_score += 100;
+                global_turn.turn += 1;
 
                 global_turn_time_score.time_score = global_takes_turn.time_score;
+                global_turn_time_score.turn = global_turn.turn;
 
                 console_log(format!("       GlobalTurn time_score ({})", global_turn_time_score.time_score));
             }
         }
 
         if turn_taken {
+            game_log.current_turn = global_turn_time_score.turn;
+
             let mut confusions_to_remove = Vec::new();
             {
                 for (_, mut confusion, entity) in (&takes_turn, &mut confusions, &entities).join() {
@@ -65,6 +73,16 @@ impl<'a> System<'a> for GlobalTurnSystem {
             for entity in confusions_to_remove.iter() {
                 confusions.remove(*entity);
             }
+
+            for speed_modifiers in (&mut speed_modifiers).join() {
+                for modifier in speed_modifiers.modifiers.iter_mut() {
+                    if let Some(turns) = modifier.turns.as_mut() {
+                        *turns -= 1;
+                    }
+                }
+
+                speed_modifiers.modifiers.retain(|modifier| modifier.turns.is_none_or(|turns| turns > 0));
+            }
         }
     }
 }
\ No newline at end of file
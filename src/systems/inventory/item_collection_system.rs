@@ -2,39 +2,56 @@ extern crate specs;
 
 use specs::prelude::*;
 
-use crate::{GameLog, InBackpack, Name, Position, WantsToPickUp};
+use crate::{ActionKind, ActionRecorder, ActionResult, CanPickUp, InBackpack, IsVisible, LogCategory, LogMessage, Name, Position, WantsToPickUp};
 
 pub struct ItemCollectionSystem;
 
 impl<'a> System<'a> for ItemCollectionSystem {
     type SystemData = (
         ReadExpect<'a, Entity>,
-        WriteExpect<'a, GameLog>,
         WriteStorage<'a, Position>,
         WriteStorage<'a, WantsToPickUp>,
         WriteStorage<'a, InBackpack>,
         ReadStorage<'a, Name>,
+        ReadStorage<'a, IsVisible>,
+        ReadStorage<'a, CanPickUp>,
+        WriteExpect<'a, ActionRecorder>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
         let (
             player_entity,
-            mut game_log,
             mut positions,
             mut wants_to_pick_up,
             mut in_backpack,
-            names) = data;
-
+            names,
+            is_visible,
+            can_pick_up,
+            mut action_recorder,
+        ) = data;
 
         for pick_up in wants_to_pick_up.join() {
+            let collector = pick_up.collected_by;
+
+            let time_cost = can_pick_up.get(collector).expect("Unable to pick up without a pick up cost").time_cost;
+            let record = action_recorder.record(collector, ActionKind::PickUp, time_cost, ActionResult::Success);
+
             positions.remove(pick_up.item);
             in_backpack
                 .insert(pick_up.item, InBackpack { owner: pick_up.collected_by })
                 .expect("Unable to insert backpack entry");
 
+            let item_name = &names.get(pick_up.item).unwrap().name;
             if pick_up.collected_by == *player_entity {
-                let item_name = &names.get(pick_up.item).unwrap().name;
-                game_log.add(format!("You picked up {}!", item_name));
+                record.log(LogMessage::new(LogCategory::Item).text("You picked up ").item(item_name).text("!"));
+            } else if is_visible.get(pick_up.collected_by).is_some() {
+                let collector_name = &names.get(pick_up.collected_by).unwrap().name;
+                record.log(LogMessage::new(LogCategory::Item)
+                    .text("The ")
+                    .actor(collector_name)
+                    .text(" picks up a ")
+                    .item(item_name)
+                    .text("."));
             }
         }
 
@@ -2,32 +2,37 @@ extern crate specs;
 
 use specs::prelude::*;
 
-use crate::{GameLog, InBackpack, Name, Position, WantsToDrop};
+use crate::{ActionKind, ActionRecorder, ActionResult, CanDrop, InBackpack, LogCategory, LogMessage, Name, Position, WantsToDrop};
 
 pub struct ItemDropSystem;
 
 impl<'a> System<'a> for ItemDropSystem {
     type SystemData = (
         ReadExpect::<'a, Entity>,
-        WriteExpect::<'a, GameLog>,
         Entities<'a>,
         WriteStorage::<'a, WantsToDrop>,
         ReadStorage::<'a, Name>,
         WriteStorage::<'a, Position>,
-        WriteStorage::<'a, InBackpack>);
+        WriteStorage::<'a, InBackpack>,
+        ReadStorage::<'a, CanDrop>,
+        WriteExpect::<'a, ActionRecorder>);
 
     fn run(&mut self, data: Self::SystemData) {
         let (
             player_entity,
-            mut game_log,
             entities,
             mut wants_to_drop,
             names,
             mut positions,
-            mut in_backpacks
+            mut in_backpacks,
+            can_drop,
+            mut action_recorder,
         ) = data;
 
         for (entity, to_drop) in (&entities, &wants_to_drop).join() {
+            let time_cost = can_drop.get(entity).expect("Unable to drop without a drop cost").time_cost;
+            let record = action_recorder.record(entity, ActionKind::Drop, time_cost, ActionResult::Success);
+
             let drop_position: Position;
             { drop_position = *positions.get(entity).unwrap(); }
 
@@ -40,7 +45,7 @@ impl<'a> System<'a> for ItemDropSystem {
 
             let name = &names.get(to_drop.item).unwrap().name;
             if entity == *player_entity {
-                game_log.add(format!("You dropped the {}", name));
+                record.log(LogMessage::new(LogCategory::Item).text("You dropped the ").item(name));
             }
         }
 
@@ -5,14 +5,13 @@ use std::cmp::min;
 use rltk::{Algorithm2D, ColorPair, Point, RGB};
 use specs::prelude::*;
 
-use crate::{AreaOfEffect, CombatStats, Confusion, Consumable, GameLog, InflictsDamage, LONG_LIFETIME, Map, MEDIUM_LIFETIME, Name, ParticleBuilder, Position, ProvidesHealing, SuffersDamage, WantsToUseItem};
+use crate::{ActionKind, ActionRecorder, ActionResult, AreaOfEffect, CanUseItem, CombatStats, Confusion, Consumable, EXPLOSION_NOISE, GameEvent, InflictsDamage, IsVisible, Item, LogCategory, LogMessage, LONG_LIFETIME, Map, MEDIUM_LIFETIME, ModifiesSpeed, Name, NoiseBuilder, ParticleBuilder, Position, ProvidesHealing, SpeedModifiers, StatusEffect, SuffersDamage, UseTimeCost, WantsToUseItem};
 
 pub struct ItemUseSystem;
 
 impl<'a> System<'a> for ItemUseSystem {
     type SystemData = (
         ReadExpect<'a, Entity>,
-        WriteExpect<'a, GameLog>,
         ReadExpect<'a, Map>,
         Entities<'a>,
         ReadStorage<'a, Name>,
@@ -26,12 +25,21 @@ impl<'a> System<'a> for ItemUseSystem {
         WriteStorage<'a, Confusion>,
         WriteExpect<'a, ParticleBuilder>,
         ReadStorage<'a, Position>,
+        ReadStorage<'a, Item>,
+        ReadStorage<'a, IsVisible>,
+        WriteExpect<'a, NoiseBuilder>,
+        ReadStorage<'a, ModifiesSpeed>,
+        WriteStorage<'a, SpeedModifiers>,
+        WriteExpect<'a, ActionRecorder>,
+        (
+            ReadStorage<'a, CanUseItem>,
+            ReadStorage<'a, UseTimeCost>,
+        ),
     );
 
     fn run(&mut self, data: Self::SystemData) {
         let (
             player_entity,
-            mut game_log,
             map,
             entities,
             names,
@@ -45,15 +53,45 @@ impl<'a> System<'a> for ItemUseSystem {
             mut confusion,
             mut particle_builder,
             positions,
+            items,
+            is_visible,
+            mut noise_builder,
+            modifies_speed,
+            mut speed_modifiers,
+            mut action_recorder,
+            (
+                can_use_item,
+                use_time_cost,
+            ),
         ) = data;
 
         for (user_entity, use_item) in (&entities, &wants_to_use_items).join() {
             let mut used_item = false;
             let item_entity = use_item.item;
 
+            let time_cost = use_time_cost
+                .get(item_entity)
+                .map(|use_time_cost| use_time_cost.time_cost)
+                .or_else(|| can_use_item.get(user_entity).map(|can_use_item| can_use_item.time_cost))
+                .expect("Unable to use an item without a use cost");
+            let mut messages = Vec::new();
+            let mut events = Vec::new();
+
+            if user_entity != *player_entity && is_visible.get(user_entity).is_some() {
+                let user_name = &names.get(user_entity).unwrap().name;
+                let item_name = &names.get(item_entity).unwrap().name;
+                let use_verb = items.get(item_entity).map_or("uses", |item| item.kind.use_verb());
+                messages.push(LogMessage::new(LogCategory::Item)
+                    .text("The ")
+                    .actor(user_name)
+                    .text(format!(" {} a ", use_verb))
+                    .item(item_name)
+                    .text("!"));
+            }
+
             let mut targets: Vec<Entity> = Vec::new();
             match use_item.target {
-                None => targets.push(*player_entity),
+                None => targets.push(user_entity),
                 Some(target) => {
                     let area_of_effect = aoe.get(use_item.item);
                     match area_of_effect {
@@ -69,6 +107,8 @@ impl<'a> System<'a> for ItemUseSystem {
                                 area_of_effect.radius,
                                 &*map);
 
+                            noise_builder.request_noise(target, EXPLOSION_NOISE, user_entity);
+
                             let valid_blast_tiles = blast_tiles
                                 .iter()
                                 .filter(|p| map.in_bounds(**p))
@@ -111,7 +151,12 @@ impl<'a> System<'a> for ItemUseSystem {
 
                         if user_entity == *player_entity {
                             let item_name = &names.get(item_entity).unwrap().name;
-                            game_log.add(format!("You use {}, healing {} hp.", item_name, heal_item.heal_amount));
+                            messages.push(LogMessage::new(LogCategory::Item)
+                                .text("You use ")
+                                .item(item_name)
+                                .text(", healing ")
+                                .amount(heal_item.heal_amount)
+                                .text(" hp."));
                         }
 
                         if let Some(position) = positions.get(**target) {
@@ -129,20 +174,64 @@ impl<'a> System<'a> for ItemUseSystem {
             let damage_item = inflicts_damage.get(item_entity);
             if let Some(damage_item) = damage_item {
                 for target in stat_targets.iter() {
-                    suffers_damage
-                        .insert(
-                            **target,
-                            SuffersDamage {
-                                amount: damage_item.damage
-                            })
-                        .expect("Unable to insert");
+                    SuffersDamage::new_damage(
+                        &mut suffers_damage,
+                        **target,
+                        damage_item.damage,
+                        damage_item.damage_type,
+                        user_entity);
 
                     used_item = true;
 
                     if user_entity == *player_entity {
                         let item_name = &names.get(item_entity).unwrap().name;
                         let mob_name = &names.get(**target).unwrap().name;
-                        game_log.add(format!("You use {} on {}, inflicting {} hp.", item_name, mob_name, damage_item.damage));
+                        messages.push(LogMessage::new(LogCategory::Item)
+                            .text("You use ")
+                            .item(item_name)
+                            .text(" on ")
+                            .actor(mob_name)
+                            .text(", inflicting ")
+                            .amount(damage_item.damage)
+                            .text(format!(" {} damage.", damage_item.damage_type))
+                            .important_if(**target == *player_entity));
+                    }
+                }
+            }
+
+            if let Some(speed_item) = modifies_speed.get(item_entity) {
+                for target in stat_targets.iter() {
+                    if let Some(target_speed_modifiers) = speed_modifiers.get_mut(**target) {
+                        target_speed_modifiers.set(speed_item.source, speed_item.multiplier, Some(speed_item.turns));
+                        events.push(GameEvent::StatusApplied {
+                            target: **target,
+                            status: StatusEffect::Speed(speed_item.source),
+                            turns: speed_item.turns,
+                        });
+
+                        used_item = true;
+
+                        let mob_name = &names.get(**target).unwrap().name;
+                        let (verb, glyph, fg) = match speed_item.multiplier < 1. {
+                            true => ("speeds up", '»', rltk::RGB::named(rltk::CYAN)),
+                            false => ("slows down", '«', rltk::RGB::named(rltk::SADDLEBROWN)),
+                        };
+
+                        if is_visible.get(**target).is_some() {
+                            messages.push(LogMessage::new(LogCategory::Status)
+                                .actor(mob_name)
+                                .colored(format!(" {}", verb), fg)
+                                .text("!"));
+                        }
+
+                        if let Some(position) = positions.get(**target) {
+                            particle_builder.request_aura(
+                                Point::new(position.x, position.y),
+                                MEDIUM_LIFETIME,
+                                fg,
+                                rltk::to_cp437(glyph),
+                            );
+                        }
                     }
                 }
             }
@@ -158,13 +247,21 @@ impl<'a> System<'a> for ItemUseSystem {
                     if user_entity == *player_entity {
                         let item_name = &names.get(item_entity).unwrap().name;
                         let mob_name = &names.get(**target).unwrap().name;
-                        game_log.add(format!("You use {} on {}, confusing them.", item_name, mob_name));
+                        messages.push(LogMessage::new(LogCategory::Status)
+                            .text("You use ")
+                            .item(item_name)
+                            .text(" on ")
+                            .actor(mob_name)
+                            .text(", ")
+                            .colored("confusing", rltk::RGB::named(rltk::MAGENTA))
+                            .text(" them."));
                     }
                 }
             }
 
             for (mob, turns) in mobs_to_confuse.iter() {
                 confusion.insert(***mob, Confusion { turns: *turns }).expect("Unable to insert status");
+                events.push(GameEvent::StatusApplied { target: ***mob, status: StatusEffect::Confusion, turns: *turns });
 
                 if let Some(position) = positions.get(***mob) {
                     particle_builder.request_aura(
@@ -177,10 +274,24 @@ impl<'a> System<'a> for ItemUseSystem {
             }
 
             if used_item {
+                events.push(GameEvent::ItemUsed { user: user_entity, item: item_entity });
+
                 if let Some(_consumable) = consumables.get(item_entity) {
                     entities.delete(item_entity).expect("Delete failed");
                 }
             }
+
+            let result = match used_item {
+                true => ActionResult::Success,
+                false => ActionResult::Failed,
+            };
+            let record = action_recorder.record(user_entity, ActionKind::UseItem, time_cost, result);
+            for message in messages {
+                record.log(message);
+            }
+            for event in events {
+                record.event(event);
+            }
         }
 
         wants_to_use_items.clear();
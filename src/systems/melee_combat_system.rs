@@ -1,68 +1,179 @@
 extern crate specs;
 
+use rltk::{DiceType, Point, RGB};
 use specs::prelude::*;
 
-use crate::{CanMelee, CombatStats, GameLog, Name, SuffersDamage, TakesTurn, WantsToMelee};
+use crate::{ActionKind, ActionRecorder, ActionResult, CanMelee, CombatStats, DamageType, GameEvent, LogCategory, LogMessage, LONG_LIFETIME, MEDIUM_LIFETIME, MELEE_NOISE, Name, NoiseBuilder, ParticleBuilder, Position, RNG, SHORT_LIFETIME, SuffersDamage, WantsToMelee};
+
+const ATTACK_DIE: i32 = 20;
+const BASE_ARMOR_CLASS: i32 = 10;
 
 pub struct MeleeCombatSystem;
 
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum AttackRoll {
+    Fumble,
+    Miss,
+    Hit { damage: i32 },
+    Critical { damage: i32 },
+}
+
+impl AttackRoll {
+    /// Rolls a d20 plus the attacker's power against the target's defense.
+    /// A natural 1 always fumbles and a natural 20 always crits, rolling the damage dice twice.
+    pub fn roll(power: i32, defense: i32, damage: DiceType) -> AttackRoll {
+        let attack_roll = RNG.roll_die(ATTACK_DIE);
+
+        if attack_roll == 1 {
+            AttackRoll::Fumble
+        } else if attack_roll == ATTACK_DIE {
+            let damage = RNG.roll(damage) + RNG.roll(damage);
+            AttackRoll::Critical { damage: i32::max(1, damage) }
+        } else if attack_roll + power >= BASE_ARMOR_CLASS + defense {
+            AttackRoll::Hit { damage: i32::max(1, RNG.roll(damage)) }
+        } else {
+            AttackRoll::Miss
+        }
+    }
+}
+
 impl<'a> System<'a> for MeleeCombatSystem {
     type SystemData = (
-        WriteExpect<'a, GameLog>,
+        Entities<'a>,
         WriteStorage<'a, WantsToMelee>,
         ReadStorage<'a, Name>,
         ReadStorage<'a, CombatStats>,
         WriteStorage<'a, SuffersDamage>,
-        WriteStorage<'a, TakesTurn>,
         ReadStorage<'a, CanMelee>,
+        WriteExpect<'a, ParticleBuilder>,
+        ReadStorage<'a, Position>,
+        WriteExpect<'a, NoiseBuilder>,
+        WriteExpect<'a, ActionRecorder>,
+        ReadExpect<'a, Entity>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
         let (
-            mut game_log,
+            entities,
             mut wants_melee,
             names,
             combat_stats,
             mut suffers_damage,
-            mut takes_turn,
             can_melee,
+            mut particle_builder,
+            positions,
+            mut noise_builder,
+            mut action_recorder,
+            player_entity,
         ) = data;
 
-        for (wants_melee, name, stats, mut takes_turn, can_melee) in (&wants_melee, &names, &combat_stats, &mut takes_turn, &can_melee).join() {
-            takes_turn.time_score += can_melee.time_cost;
+        for (entity, wants_melee, name, stats, can_melee) in (&entities, &wants_melee, &names, &combat_stats, &can_melee).join() {
+            let target_stats = combat_stats.get(wants_melee.target).unwrap();
 
-            if stats.hp <= 0 {
+            if stats.hp <= 0 || target_stats.hp <= 0 {
+                action_recorder.record(entity, ActionKind::Melee, can_melee.time_cost, ActionResult::Failed);
                 continue;
             }
 
-            let target_stats = combat_stats.get(wants_melee.target).unwrap();
+            let record = action_recorder.record(entity, ActionKind::Melee, can_melee.time_cost, ActionResult::Success);
 
-            if target_stats.hp <= 0 {
-                continue;
+            let target_name = names.get(wants_melee.target).unwrap();
+            let target_position = positions.get(wants_melee.target).map(|p| Point::new(p.x, p.y));
+            let is_target_player = wants_melee.target == *player_entity;
+
+            if let Some(position) = positions.get(entity) {
+                noise_builder.request_noise(Point::new(position.x, position.y), MELEE_NOISE, entity);
             }
 
-            let target_name = names.get(wants_melee.target).unwrap();
+            let attack_roll = AttackRoll::roll(stats.power, target_stats.defense, can_melee.damage);
+            record.event(GameEvent::Attacked { attacker: entity, target: wants_melee.target, roll: attack_roll });
+
+            match attack_roll {
+                AttackRoll::Fumble => {
+                    record.log(LogMessage::new(LogCategory::Combat)
+                        .actor(&name.name)
+                        .text(" fumbles the attack on ")
+                        .actor(&target_name.name)
+                        .text("!"));
+
+                    if let Some(position) = positions.get(entity) {
+                        particle_builder.request_aura(
+                            Point::new(position.x, position.y),
+                            MEDIUM_LIFETIME,
+                            RGB::named(rltk::LIGHT_SALMON),
+                            rltk::to_cp437('¿'),
+                        );
+                    }
+                }
+                AttackRoll::Miss => {
+                    record.log(LogMessage::new(LogCategory::Combat)
+                        .actor(&name.name)
+                        .text(" misses ")
+                        .actor(&target_name.name)
+                        .text("."));
+
+                    if let Some(target_position) = target_position {
+                        particle_builder.request_aura(
+                            target_position,
+                            MEDIUM_LIFETIME,
+                            RGB::named(rltk::LIGHT_GRAY),
+                            rltk::to_cp437('○'),
+                        );
+                    }
+                }
+                AttackRoll::Hit { damage } => {
+                    record.log(LogMessage::new(LogCategory::Combat)
+                        .actor(&name.name)
+                        .text(" hits ")
+                        .actor(&target_name.name)
+                        .text(" for ")
+                        .amount(damage)
+                        .text(" hp.")
+                        .important_if(is_target_player));
+
+                    if let Some(target_position) = target_position {
+                        particle_builder.request_background(
+                            target_position,
+                            SHORT_LIFETIME,
+                            RGB::named(rltk::DARK_RED),
+                        );
+                    }
+
+                    SuffersDamage::new_damage(
+                        &mut suffers_damage,
+                        wants_melee.target,
+                        damage,
+                        DamageType::Physical,
+                        entity);
+                }
+                AttackRoll::Critical { damage } => {
+                    record.log(LogMessage::new(LogCategory::Combat)
+                        .actor(&name.name)
+                        .text(" critically hits ")
+                        .actor(&target_name.name)
+                        .text(" for ")
+                        .amount(damage)
+                        .text(" hp!")
+                        .important_if(is_target_player));
+
+                    if let Some(target_position) = target_position {
+                        particle_builder.request_background(
+                            target_position,
+                            LONG_LIFETIME,
+                            RGB::named(rltk::GOLD),
+                        );
+                    }
 
-            let damage = i32::max(0, stats.power - target_stats.defense);
-
-            if damage == 0 {
-                game_log.add(format!(
-                    "{} is unable to hurt {}.",
-                    &name.name,
-                    &target_name.name));
-            } else {
-                game_log.add(format!(
-                    "{} hits {} for {} hp.",
-                    &name.name,
-                    &target_name.name,
-                    damage));
-
-                suffers_damage
-                    .insert(wants_melee.target, SuffersDamage { amount: damage })
-                    .expect("Unable to do damage");
+                    SuffersDamage::new_damage(
+                        &mut suffers_damage,
+                        wants_melee.target,
+                        damage,
+                        DamageType::Physical,
+                        entity);
+                }
             }
         }
 
         wants_melee.clear();
     }
-}
\ No newline at end of file
+}
@@ -1,16 +1,25 @@
+pub use action_system::*;
 pub use damage_system::*;
+pub use death_log_system::*;
 pub use global_turn_system::*;
 pub use inventory::*;
 pub use map_indexing_system::*;
 pub use melee_combat_system::*;
 pub use monster_ai_system::*;
 pub use movement_system::*;
+pub use noise_system::*;
 pub use particle_system::*;
+pub use ranged_combat_system::*;
+pub use recruit_system::*;
+pub use speed_system::*;
+pub use statistics_system::*;
 pub use visibility_system::*;
 pub use wait_system::*;
 
+pub mod action_system;
 pub mod wait_system;
 pub mod damage_system;
+pub mod death_log_system;
 pub mod visibility_system;
 pub mod monster_ai_system;
 pub mod map_indexing_system;
@@ -18,4 +27,9 @@ pub mod melee_combat_system;
 pub mod inventory;
 pub mod global_turn_system;
 pub mod movement_system;
-pub mod particle_system;
\ No newline at end of file
+pub mod noise_system;
+pub mod particle_system;
+pub mod ranged_combat_system;
+pub mod recruit_system;
+pub mod speed_system;
+pub mod statistics_system;
\ No newline at end of file
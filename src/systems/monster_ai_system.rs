@@ -1,10 +1,12 @@
 extern crate rltk;
 extern crate specs;
 
-use rltk::Point;
+use std::collections::HashSet;
+
+use rltk::{DijkstraMap, Point, RGB};
 use specs::prelude::*;
 
-use crate::{Confusion, console_log, Map, Monster, Name, Position, RNG, Viewshed, WaitCause, WantsToMelee, WantsToMove, WantsToTakeTurn, WantsToWait};
+use crate::{ActionScoring, ai_profile, AiProfile, Ally, AreaOfEffect, Awareness, AwarenessState, CanShoot, CombatStats, Confusion, Consideration, console_log, DEBUG, Faction, FactionTable, GameLog, InBackpack, InflictsDamage, IsVisible, Item, LogCategory, LogMessage, Map, MEDIUM_LIFETIME, Monster, Morale, Name, ParticleBuilder, PartyLeader, PartyMember, PlayerMemory, Position, ProvidesHealing, Ranged, Reaction, RNG, Scattering, UtilityAction, Viewshed, WaitCause, WantsToMelee, WantsToMove, WantsToPickUp, WantsToShoot, WantsToTakeTurn, WantsToUseItem, WantsToWait};
 
 use self::rltk::Algorithm2D;
 
@@ -19,6 +21,9 @@ enum MonsterTurnAction {
     Melee(Entity),
     Move(Point),
     Wait { is_confused: bool },
+    PickUp(Entity),
+    UseItem { item: Entity, target: Option<Point> },
+    Shoot(Entity),
 }
 
 impl<'a> System<'a> for MonsterAI {
@@ -36,6 +41,40 @@ impl<'a> System<'a> for MonsterAI {
         WriteStorage<'a, WantsToWait>,
         ReadStorage<'a, Confusion>,
         ReadStorage<'a, Name>,
+        ReadStorage<'a, CombatStats>,
+        (
+            ReadStorage<'a, Item>,
+            ReadStorage<'a, InBackpack>,
+            ReadStorage<'a, ProvidesHealing>,
+            ReadStorage<'a, Ranged>,
+            ReadStorage<'a, InflictsDamage>,
+            ReadStorage<'a, AreaOfEffect>,
+        ),
+        WriteStorage<'a, WantsToPickUp>,
+        WriteStorage<'a, WantsToUseItem>,
+        ReadStorage<'a, PartyLeader>,
+        ReadStorage<'a, PartyMember>,
+        WriteStorage<'a, Scattering>,
+        (
+            WriteStorage<'a, PlayerMemory>,
+            WriteStorage<'a, Awareness>,
+            ReadStorage<'a, AiProfile>,
+        ),
+        WriteStorage<'a, Morale>,
+        (
+            WriteExpect<'a, GameLog>,
+            ReadStorage<'a, IsVisible>,
+            WriteExpect<'a, ParticleBuilder>,
+        ),
+        (
+            ReadStorage<'a, CanShoot>,
+            WriteStorage<'a, WantsToShoot>,
+        ),
+        (
+            ReadStorage<'a, Faction>,
+            ReadExpect<'a, FactionTable>,
+            ReadStorage<'a, Ally>,
+        ),
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -53,61 +92,381 @@ impl<'a> System<'a> for MonsterAI {
             mut wants_to_wait,
             confusions,
             names,
+            combat_stats,
+            (
+                items,
+                in_backpacks,
+                provides_healing,
+                ranged,
+                inflicts_damage,
+                aoe,
+            ),
+            mut wants_to_pick_up,
+            mut wants_to_use_item,
+            party_leaders,
+            party_members,
+            mut scatterings,
+            (
+                mut player_memories,
+                mut awarenesses,
+                ai_profiles,
+            ),
+            mut morales,
+            (
+                mut game_log,
+                is_visible,
+                mut particle_builder,
+            ),
+            (
+                can_shoot,
+                mut wants_to_shoot,
+            ),
+            (
+                factions,
+                faction_table,
+                allies,
+            ),
         ) = data;
 
         let map = &mut *map;
         let player_pos = *player_pos;
         let player_idx = map.point2d_to_index(player_pos);
 
-        let get_action = |entity: Entity, position: &Position, viewshed: &Viewshed| -> MonsterTurnAction {
+        let get_party = |entity: Entity| -> Option<Entity> {
+            if party_leaders.get(entity).is_some() {
+                Some(entity)
+            } else {
+                party_members.get(entity).map(|party_member| party_member.leader)
+            }
+        };
+
+        let is_asleep = |entity: Entity| -> bool {
+            awarenesses.get(entity).is_some_and(|awareness| awareness.state == AwarenessState::Asleep)
+        };
+
+        let alerted_parties = (&entities, &viewsheds)
+            .join()
+            .filter(|(entity, viewshed)| !is_asleep(*entity) && viewshed.visible_tiles.contains(&player_pos))
+            .filter_map(|(entity, _)| get_party(entity))
+            .collect::<HashSet<Entity>>();
+
+        let needs_flee_map = (&morales, &wants_to_take_turn, !&allies)
+            .join()
+            .any(|(morale, _, _)| morale.is_broken || morale.morale <= BREAK_MORALE);
+
+        let flee_map = match needs_flee_map {
+            false => None,
+            true => Some(DijkstraMap::new(map.width, map.height, &[player_idx], &*map, FLEE_MAP_DEPTH)),
+        };
+
+        let is_hostile_to_player = |entity: Entity| -> bool {
+            faction_table.get_entity_reaction(&factions, entity, *player_entity) == Reaction::Hostile
+        };
+
+        let get_nearest_hostile = |entity: Entity, position: &Position, viewshed: &Viewshed| -> Option<(Entity, Point)> {
+            let position = Point::new(position.x, position.y);
+
+            viewshed.visible_tiles
+                .iter()
+                .flat_map(|tile| {
+                    let tile_idx = map.point2d_to_index(*tile);
+                    map.tile_content[tile_idx].iter().map(move |tile_entity| (*tile_entity, *tile))
+                })
+                .filter(|(other, _)| *other != entity && combat_stats.get(*other).is_some())
+                .filter(|(other, _)| faction_table.get_entity_reaction(&factions, entity, *other) == Reaction::Hostile)
+                .min_by(|(_, a), (_, b)| {
+                    let a_distance = rltk::DistanceAlg::Pythagoras.distance2d(position, *a);
+                    let b_distance = rltk::DistanceAlg::Pythagoras.distance2d(position, *b);
+                    a_distance.partial_cmp(&b_distance).unwrap()
+                })
+        };
+
+        let get_action = |entity: Entity, position: &Position, viewshed: &Viewshed, memory: Option<PlayerMemory>, is_broken: bool, name: &str| -> MonsterTurnAction {
             if confusions.get(entity).is_some() {
+                let adjacent_targets = CARDINAL_DELTAS
+                    .iter()
+                    .map(|(delta_x, delta_y)| Point::new(position.x + delta_x, position.y + delta_y))
+                    .filter(|tile| map.is_valid(tile.x, tile.y))
+                    .flat_map(|tile| map.tile_content[map.point2d_to_index(tile)].iter())
+                    .filter(|tile_entity| combat_stats.get(**tile_entity).is_some())
+                    .copied()
+                    .collect::<Vec<Entity>>();
+
+                if !adjacent_targets.is_empty() && RNG.flip_coin() {
+                    let choice = RNG.range(0, adjacent_targets.len() as i32) as usize;
+                    return MonsterTurnAction::Melee(adjacent_targets[choice]);
+                }
+
                 return MonsterTurnAction::Wait { is_confused: true };
             }
 
-            let distance = rltk::DistanceAlg::Pythagoras.distance2d(Point::new(position.x, position.y), player_pos);
+            let party = get_party(entity);
+            let is_party_alerted = is_hostile_to_player(entity) && party.is_some_and(|party| alerted_parties.contains(&party));
+
+            let target = get_nearest_hostile(entity, position, viewshed).or(match is_party_alerted {
+                true => Some((*player_entity, player_pos)),
+                false => None,
+            });
+
+            let backpack = (&entities, &in_backpacks)
+                .join()
+                .filter(|(_, in_backpack)| in_backpack.owner == entity)
+                .map(|(item, _)| item)
+                .collect::<Vec<Entity>>();
 
             const IS_ADJACENT_DISTANCE: f32 = 1.01;
 
-            if distance < IS_ADJACENT_DISTANCE {
-                return MonsterTurnAction::Melee(*player_entity);
-            } else if viewshed.visible_tiles.contains(&player_pos) {
-                let monster_idx = map.xy_idx(position.x, position.y);
-                let path = rltk::a_star_search(monster_idx, player_idx, map);
+            let monster_idx = map.xy_idx(position.x, position.y);
+            let monster_pos = Point::new(position.x, position.y);
+            let target_distance = |target_pos: Point| -> f32 {
+                rltk::DistanceAlg::Pythagoras.distance2d(monster_pos, target_pos)
+            };
+
+            let mut candidates: Vec<(UtilityAction, MonsterTurnAction)> = Vec::new();
+
+            if let Some(healing_item) = backpack.iter().find(|item| provides_healing.get(**item).is_some()) {
+                candidates.push((UtilityAction::Heal, MonsterTurnAction::UseItem { item: *healing_item, target: None }));
+            }
+
+            // The flee map runs from the player, whom allies follow, so they run from the nearest hostile instead.
+            let flee_step = match allies.get(entity) {
+                Some(_) => target.and_then(|(_, target_pos)| step_away_from(map, monster_pos, target_pos)),
+                None => flee_map
+                    .as_ref()
+                    .and_then(|flee_map| DijkstraMap::find_highest_exit(flee_map, monster_idx, &*map))
+                    .map(|flee_idx| map.index_to_point2d(flee_idx)),
+            };
 
-                const FIRST_STEP_INDEX: usize = 1;
+            if let Some(flee_step) = flee_step {
+                candidates.push((UtilityAction::Flee, MonsterTurnAction::Move(flee_step)));
+            }
 
-                if path.success && path.steps.len() > FIRST_STEP_INDEX {
-                    let first_step_idx = path.steps[FIRST_STEP_INDEX];
-                    let first_step = map.index_to_point2d(first_step_idx);
-                    return MonsterTurnAction::Move(first_step);
-                } else {
-                    return MonsterTurnAction::Wait { is_confused: false };
+            if let Some(scattering) = scatterings.get(entity) {
+                if let Some(step) = step_away_from(map, monster_pos, scattering.from) {
+                    candidates.push((UtilityAction::Scatter, MonsterTurnAction::Move(step)));
                 }
-            } else {
-                let delta: (i32, i32);
+            }
 
-                match RNG.roll_die(4) {
-                    1 => delta = (1, 0),
-                    2 => delta = (-1, 0),
-                    3 => delta = (0, 1),
-                    4 => delta = (0, -1),
-                    _ => delta = (0, 0),
+            if let Some((target_entity, target_pos)) = target {
+                let distance = target_distance(target_pos);
+                let can_see_target = viewshed.visible_tiles.contains(&target_pos);
+
+                if distance < IS_ADJACENT_DISTANCE {
+                    candidates.push((UtilityAction::Melee, MonsterTurnAction::Melee(target_entity)));
                 }
 
-                let (delta_x, delta_y) = delta;
+                if can_see_target && distance >= IS_ADJACENT_DISTANCE {
+                    let throwable_item = backpack.iter().find(|item| {
+                        let in_range = ranged.get(**item).is_some_and(|ranged| distance <= ranged.range as f32);
+                        let is_harmful = inflicts_damage.get(**item).is_some() || confusions.get(**item).is_some();
+                        let is_outside_blast = aoe.get(**item).is_none_or(|aoe| distance > aoe.radius as f32);
 
-                let next_step = Point::new(position.x + delta_x, position.y + delta_y);
+                        in_range && is_harmful && is_outside_blast
+                    });
 
-                if RNG.roll_die(7) > 1 {
-                    return MonsterTurnAction::Move(next_step);
-                } else {
-                    return MonsterTurnAction::Wait { is_confused: false };
+                    if let Some(throwable_item) = throwable_item {
+                        candidates.push((UtilityAction::Throw, MonsterTurnAction::UseItem { item: *throwable_item, target: Some(target_pos) }));
+                    }
+                }
+
+                let ranged_attack = can_shoot
+                    .get(entity)
+                    .filter(|can_shoot| can_shoot.has_ammo() && distance <= can_shoot.range as f32);
+
+                if let (true, Some(ranged_attack)) = (can_see_target, ranged_attack) {
+                    candidates.push((UtilityAction::Shoot, MonsterTurnAction::Shoot(target_entity)));
+
+                    if distance < ranged_attack.preferred_distance as f32 {
+                        if let Some(step) = step_away_from(map, monster_pos, target_pos) {
+                            candidates.push((UtilityAction::Retreat, MonsterTurnAction::Move(step)));
+                        }
+                    }
+                }
+
+                let target_idx = map.point2d_to_index(target_pos);
+                let approach_idx = match party {
+                    None => target_idx,
+                    Some(_) => get_surround_tile(map, monster_pos, target_pos)
+                        .map_or(target_idx, |tile| map.point2d_to_index(tile)),
+                };
+
+                if let Some(first_step) = first_step_towards(map, monster_idx, approach_idx) {
+                    candidates.push((UtilityAction::Approach, MonsterTurnAction::Move(first_step)));
+                }
+            }
+
+            let item_underfoot = map.tile_content[monster_idx]
+                .iter()
+                .find(|tile_entity| items.get(**tile_entity).is_some());
+
+            if let Some(item_underfoot) = item_underfoot {
+                candidates.push((UtilityAction::PickUp, MonsterTurnAction::PickUp(*item_underfoot)));
+            }
+
+            if let Some(memory) = memory {
+                let last_seen_idx = map.point2d_to_index(memory.last_seen);
+                let step = match target_distance(memory.last_seen) > SEARCH_RADIUS {
+                    true => first_step_towards(map, monster_idx, last_seen_idx),
+                    false => None,
+                }.or_else(|| search_step(map, monster_pos, memory.last_seen));
+
+                if let Some(step) = step {
+                    candidates.push((UtilityAction::Search, MonsterTurnAction::Move(step)));
                 }
             }
+
+            let leader_position = party
+                .filter(|leader| *leader != entity)
+                .or_else(|| allies.get(entity).map(|ally| ally.leader))
+                .and_then(|leader| positions.get(leader));
+
+            if let Some(leader_position) = leader_position {
+                let leader_distance = target_distance(Point::new(leader_position.x, leader_position.y));
+
+                const FOLLOW_DISTANCE: f32 = 3.;
+
+                if leader_distance > FOLLOW_DISTANCE {
+                    let leader_idx = map.xy_idx(leader_position.x, leader_position.y);
+
+                    if let Some(first_step) = first_step_towards(map, monster_idx, leader_idx) {
+                        candidates.push((UtilityAction::Follow, MonsterTurnAction::Move(first_step)));
+                    }
+                }
+            }
+
+            let (delta_x, delta_y) = CARDINAL_DELTAS[RNG.range(0, CARDINAL_DELTAS.len() as i32) as usize];
+            candidates.push((UtilityAction::Wander, MonsterTurnAction::Move(Point::new(position.x + delta_x, position.y + delta_y))));
+            candidates.push((UtilityAction::Wait, MonsterTurnAction::Wait { is_confused: false }));
+
+            let default_profile;
+            let profile = match ai_profiles.get(entity) {
+                None => {
+                    default_profile = ai_profile(name);
+                    &default_profile
+                }
+                Some(profile) => profile,
+            };
+
+            let context = UtilityContext {
+                health_ratio: combat_stats
+                    .get(entity)
+                    .map_or(1., |stats| stats.hp as f32 / i32::max(1, stats.max_hp) as f32),
+                is_broken,
+                target_distance: target.map(|(_, target_pos)| target_distance(target_pos)),
+                preferred_distance: can_shoot.get(entity).map(|can_shoot| can_shoot.preferred_distance as f32),
+            };
+
+            let scored_candidates = candidates
+                .into_iter()
+                .filter_map(|(utility_action, action)| {
+                    profile
+                        .get_scoring(utility_action)
+                        .map(|scoring| (utility_action, action, score_action(scoring, &context)))
+                })
+                .collect::<Vec<(UtilityAction, MonsterTurnAction, f32)>>();
+
+            if DEBUG {
+                let scores = scored_candidates
+                    .iter()
+                    .map(|(utility_action, _, score)| format!("{:?} {:.2}", utility_action, score))
+                    .collect::<Vec<String>>();
+
+                console_log(format!("           {} scores: {}", name, scores.join(", ")));
+            }
+
+            scored_candidates
+                .into_iter()
+                .max_by(|(_, _, a), (_, _, b)| a.partial_cmp(b).unwrap())
+                .map_or(MonsterTurnAction::Wait { is_confused: false }, |(_, action, _)| action)
         };
 
+        let mut scatterings_to_tick = Vec::new();
+        let mut helpers_to_alert = Vec::new();
+
+        let mut awareness_changes = Vec::new();
+
         for (entity, _monster, position, _turn, viewshed, name) in (&entities, &monster, &positions, &wants_to_take_turn, &viewsheds, &names).join() {
-            let action = get_action(entity, position, viewshed);
+            if is_asleep(entity) {
+                wants_to_wait.insert(entity, WantsToWait { cause: WaitCause::Sleep }).expect("Unable to insert intent");
+                continue;
+            }
+
+            let is_party_alerted = get_party(entity).is_some_and(|party| alerted_parties.contains(&party));
+            let knows_player_position = viewshed.visible_tiles.contains(&player_pos) || is_party_alerted;
+
+            if knows_player_position && is_hostile_to_player(entity) {
+                player_memories
+                    .insert(entity, PlayerMemory { last_seen: player_pos, turns_since_seen: 0 })
+                    .expect("Unable to insert memory");
+            } else {
+                let is_forgotten = match player_memories.get_mut(entity) {
+                    None => false,
+                    Some(memory) => {
+                        memory.turns_since_seen += 1;
+                        memory.turns_since_seen > MEMORY_TURNS
+                    }
+                };
+
+                if is_forgotten {
+                    console_log(format!("           {} gives up the search", name.name));
+                    player_memories.remove(entity);
+                }
+            }
+
+            let can_see_player = viewshed.visible_tiles.contains(&player_pos);
+            let is_broken = match morales.get_mut(entity) {
+                None => false,
+                Some(morale) => {
+                    if !can_see_player {
+                        morale.raise(1);
+                    }
+
+                    if !morale.is_broken && morale.morale <= BREAK_MORALE {
+                        morale.is_broken = true;
+
+                        if is_visible.get(entity).is_some() {
+                            game_log.add(LogMessage::new(LogCategory::Status)
+                                .actor(&name.name)
+                                .colored(" flees", rltk::RGB::named(rltk::YELLOW))
+                                .text(", shrieking for help!"));
+                        }
+
+                        helpers_to_alert.push(Point::new(position.x, position.y));
+                    } else if morale.is_broken && morale.morale >= RECOVER_MORALE {
+                        morale.is_broken = false;
+
+                        if is_visible.get(entity).is_some() {
+                            game_log.add(LogMessage::new(LogCategory::Status).actor(&name.name).text(" regains its courage."));
+                        }
+                    }
+
+                    morale.is_broken
+                }
+            };
+
+            if is_broken {
+                particle_builder.request_aura(
+                    Point::new(position.x, position.y),
+                    MEDIUM_LIFETIME,
+                    RGB::named(rltk::YELLOW),
+                    rltk::to_cp437('!'),
+                );
+            }
+
+            let memory = player_memories.get(entity).cloned();
+            let action = get_action(entity, position, viewshed, memory, is_broken, &name.name);
+
+            let has_target = get_nearest_hostile(entity, position, viewshed).is_some() || (is_party_alerted && is_hostile_to_player(entity));
+            let awareness_state = match (has_target, player_memories.get(entity).is_some()) {
+                (true, _) => AwarenessState::Hunting,
+                (false, true) => AwarenessState::Alert,
+                (false, false) => AwarenessState::Wandering,
+            };
+            awareness_changes.push((entity, Point::new(position.x, position.y), awareness_state));
+
+            if scatterings.get(entity).is_some() {
+                scatterings_to_tick.push(entity);
+            }
 
             console_log(format!("           {}: {:?}", name.name, action));
 
@@ -126,7 +485,167 @@ impl<'a> System<'a> for MonsterAI {
 
                     wants_to_wait.insert(entity, WantsToWait { cause }).expect("Unable to insert intent");
                 }
+                MonsterTurnAction::PickUp(item) => {
+                    wants_to_pick_up.insert(entity, WantsToPickUp { collected_by: entity, item }).expect("Unable to insert intent");
+                }
+                MonsterTurnAction::UseItem { item, target } => {
+                    wants_to_use_item.insert(entity, WantsToUseItem { item, target }).expect("Unable to insert intent");
+                }
+                MonsterTurnAction::Shoot(target) => {
+                    wants_to_shoot.insert(entity, WantsToShoot { target }).expect("Unable to insert intent");
+                }
+            }
+        }
+
+        for (entity, position, awareness_state) in awareness_changes {
+            if let Some(awareness) = awarenesses.get_mut(entity) {
+                if awareness_state == AwarenessState::Hunting && awareness.state != AwarenessState::Hunting {
+                    particle_builder.request_aura(
+                        position,
+                        MEDIUM_LIFETIME,
+                        RGB::named(rltk::RED),
+                        rltk::to_cp437('!'),
+                    );
+                }
+
+                awareness.state = awareness_state;
+            }
+        }
+
+        for caller_position in helpers_to_alert.iter() {
+            for (helper, _, helper_position) in (&entities, &monster, &positions).join() {
+                let helper_distance = rltk::DistanceAlg::Pythagoras.distance2d(
+                    *caller_position,
+                    Point::new(helper_position.x, helper_position.y));
+
+                if helper_distance <= CALL_FOR_HELP_RADIUS {
+                    player_memories
+                        .insert(helper, PlayerMemory { last_seen: player_pos, turns_since_seen: 0 })
+                        .expect("Unable to insert memory");
+                }
             }
         }
+
+        for entity in scatterings_to_tick.iter() {
+            let is_done = match scatterings.get_mut(*entity) {
+                None => false,
+                Some(scattering) => {
+                    scattering.turns -= 1;
+                    scattering.turns <= 0
+                }
+            };
+
+            if is_done {
+                scatterings.remove(*entity);
+            }
+        }
+    }
+}
+
+struct UtilityContext {
+    health_ratio: f32,
+    is_broken: bool,
+    target_distance: Option<f32>,
+    preferred_distance: Option<f32>,
+}
+
+fn score_consideration(consideration: &Consideration, context: &UtilityContext) -> f32 {
+    match consideration {
+        Consideration::HealthBelow(ratio) => match context.health_ratio < *ratio {
+            true => 1.,
+            false => 0.,
+        },
+        Consideration::MoraleBroken => match context.is_broken {
+            true => 1.,
+            false => 0.,
+        },
+        Consideration::MoraleHolding => match context.is_broken {
+            true => 0.,
+            false => 1.,
+        },
+        Consideration::TargetCloseness { range } => match context.target_distance {
+            None => 0.,
+            Some(distance) if distance <= 1. || *range <= 1. => 1.,
+            Some(distance) => f32::max(0., 1. - (distance - 1.) / (range - 1.)),
+        },
+        Consideration::InsidePreferredDistance => match (context.target_distance, context.preferred_distance) {
+            (Some(distance), Some(preferred_distance)) if distance < preferred_distance => 1.,
+            _ => 0.,
+        },
+        Consideration::Random => RNG.fraction(),
     }
+}
+
+/// Multiplies the action's weight by every consideration, so any consideration scoring 0 vetoes the action.
+fn score_action(scoring: &ActionScoring, context: &UtilityContext) -> f32 {
+    scoring.considerations
+        .iter()
+        .fold(scoring.weight, |score, consideration| score * score_consideration(consideration, context))
+}
+
+const CARDINAL_DELTAS: [(i32, i32); 4] = [(0, -1), (0, 1), (1, 0), (-1, 0)];
+const MEMORY_TURNS: i32 = 20;
+const SEARCH_RADIUS: f32 = 4.;
+const BREAK_MORALE: i32 = 3;
+const RECOVER_MORALE: i32 = 7;
+const FLEE_MAP_DEPTH: f32 = 30.;
+const CALL_FOR_HELP_RADIUS: f32 = 10.;
+
+fn first_step_towards(map: &Map, start_idx: usize, end_idx: usize) -> Option<Point> {
+    let path = rltk::a_star_search(start_idx, end_idx, map);
+
+    const FIRST_STEP_INDEX: usize = 1;
+
+    if path.success && path.steps.len() > FIRST_STEP_INDEX {
+        Some(map.index_to_point2d(path.steps[FIRST_STEP_INDEX]))
+    } else {
+        None
+    }
+}
+
+fn get_open_neighbors(map: &Map, center: Point) -> Vec<Point> {
+    CARDINAL_DELTAS
+        .iter()
+        .map(|(delta_x, delta_y)| Point::new(center.x + delta_x, center.y + delta_y))
+        .filter(|tile| map.is_valid(tile.x, tile.y) && !map.is_blocked(tile.x, tile.y))
+        .collect()
+}
+
+/// Picks the free tile next to the target that is closest to the attacker, so that a party
+/// spreads out around the target instead of queueing up behind each other.
+fn get_surround_tile(map: &Map, attacker: Point, target: Point) -> Option<Point> {
+    get_open_neighbors(map, target)
+        .into_iter()
+        .min_by(|a, b| {
+            let a_distance = rltk::DistanceAlg::Pythagoras.distance2d(attacker, *a);
+            let b_distance = rltk::DistanceAlg::Pythagoras.distance2d(attacker, *b);
+            a_distance.partial_cmp(&b_distance).unwrap()
+        })
+}
+
+/// Takes a random step that keeps the monster within the search radius of where it last saw the player.
+fn search_step(map: &Map, position: Point, last_seen: Point) -> Option<Point> {
+    let candidates = get_open_neighbors(map, position)
+        .into_iter()
+        .filter(|tile| rltk::DistanceAlg::Pythagoras.distance2d(*tile, last_seen) <= SEARCH_RADIUS)
+        .collect::<Vec<Point>>();
+
+    if candidates.is_empty() {
+        return None;
+    }
+
+    let choice = RNG.range(0, candidates.len() as i32) as usize;
+
+    Some(candidates[choice])
+}
+
+fn step_away_from(map: &Map, position: Point, threat: Point) -> Option<Point> {
+    let current_distance = rltk::DistanceAlg::Pythagoras.distance2d(position, threat);
+
+    get_open_neighbors(map, position)
+        .into_iter()
+        .map(|tile| (tile, rltk::DistanceAlg::Pythagoras.distance2d(tile, threat)))
+        .filter(|(_, distance)| *distance > current_distance)
+        .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+        .map(|(tile, _)| tile)
 }
\ No newline at end of file
@@ -3,7 +3,9 @@ extern crate specs;
 use rltk::{Point, RGB};
 use specs::prelude::*;
 
-use crate::{BlocksTile, CanMove, console_log, Map, ParticleBuilder, Player, Position, SHORT_LIFETIME, TakesTurn, Viewshed, WantsToMove};
+use crate::{ActionKind, ActionRecorder, ActionResult, BlocksTile, CanMove, GameEvent, Map, NoiseBuilder, ParticleBuilder, Player, Position, SHORT_LIFETIME, Sneaking, Viewshed, WALK_NOISE, WantsToMove, WantsToSwap};
+
+pub const SNEAK_TIME_MULTIPLIER: u32 = 2;
 
 pub struct MovementSystem;
 
@@ -14,7 +16,6 @@ impl MovementSystem {
 impl<'a> System<'a> for MovementSystem {
     type SystemData = (
         WriteStorage<'a, WantsToMove>,
-        WriteStorage<'a, TakesTurn>,
         WriteStorage<'a, Position>,
         WriteStorage<'a, Viewshed>,
         Entities<'a>,
@@ -24,12 +25,15 @@ impl<'a> System<'a> for MovementSystem {
         ReadStorage<'a, CanMove>,
         ReadStorage<'a, BlocksTile>,
         WriteExpect<'a, ParticleBuilder>,
+        WriteStorage<'a, WantsToSwap>,
+        ReadStorage<'a, Sneaking>,
+        WriteExpect<'a, NoiseBuilder>,
+        WriteExpect<'a, ActionRecorder>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
         let (
             mut wants_to_move,
-            mut takes_turn,
             mut positions,
             mut viewsheds,
             entities,
@@ -39,19 +43,73 @@ impl<'a> System<'a> for MovementSystem {
             can_move,
             blocks_tiles,
             mut particle_builder,
+            mut wants_to_swap,
+            sneaking,
+            mut noise_builder,
+            mut action_recorder,
         ) = data;
 
-        for (entity, wants_to_move, mut takes_turn, mut position, can_move) in (&entities, &wants_to_move, &mut takes_turn, &mut positions, &can_move).join() {
-            takes_turn.time_score += can_move.time_cost;
+        let swaps = (&entities, &wants_to_swap, &can_move)
+            .join()
+            .map(|(entity, wants_to_swap, can_move)| (entity, wants_to_swap.target, can_move.time_cost))
+            .collect::<Vec<(Entity, Entity, u32)>>();
+
+        for (entity, target, time_cost) in swaps {
+            let (entity_position, target_position) = match (positions.get(entity), positions.get(target)) {
+                (Some(entity_position), Some(target_position)) => (*entity_position, *target_position),
+                _ => {
+                    action_recorder.record(entity, ActionKind::Swap, time_cost, ActionResult::Failed);
+                    continue;
+                }
+            };
+
+            let entity_point = Point::new(entity_position.x, entity_position.y);
+            let target_point = Point::new(target_position.x, target_position.y);
+
+            action_recorder
+                .record(entity, ActionKind::Swap, time_cost, ActionResult::Success)
+                .event(GameEvent::Moved { entity, from: entity_point, to: target_point })
+                .event(GameEvent::Moved { entity: target, from: target_point, to: entity_point });
+
+            positions.insert(entity, target_position).expect("Unable to insert position");
+            positions.insert(target, entity_position).expect("Unable to insert position");
+
+            for swapped in [entity, target].iter() {
+                if let Some(viewshed) = viewsheds.get_mut(*swapped) {
+                    viewshed.dirty = true;
+                }
+
+                if players.get(*swapped).is_some() {
+                    let position = positions.get(*swapped).unwrap();
+                    player_position.x = position.x;
+                    player_position.y = position.y;
+                }
+            }
+        }
+
+        wants_to_swap.clear();
+
+        for (entity, wants_to_move, mut position, can_move) in (&entities, &wants_to_move, &mut positions, &can_move).join() {
+            let is_sneaking = sneaking.get(entity).is_some();
+
+            let time_cost = match is_sneaking {
+                true => can_move.time_cost * SNEAK_TIME_MULTIPLIER,
+                false => can_move.time_cost,
+            };
 
             let new_position = wants_to_move.destination;
 
             if map.is_blocked(new_position.x, new_position.y) {
+                action_recorder.record(entity, ActionKind::Move, time_cost, ActionResult::Failed);
                 continue;
             }
 
             let old_position = Point::new(position.x, position.y);
 
+            action_recorder
+                .record(entity, ActionKind::Move, time_cost, ActionResult::Success)
+                .event(GameEvent::Moved { entity, from: old_position, to: new_position });
+
             let old_position_idx = map.xy_idx(position.x, position.y);
             let new_position_idx = map.xy_idx(new_position.x, new_position.y);
             position.x = new_position.x;
@@ -77,6 +135,10 @@ impl<'a> System<'a> for MovementSystem {
             if is_player {
                 player_position.x = new_position.x;
                 player_position.y = new_position.y;
+
+                if !is_sneaking {
+                    noise_builder.request_noise(new_position, WALK_NOISE, entity);
+                }
             }
         }
 
@@ -0,0 +1,122 @@
+extern crate specs;
+
+use rltk::{Algorithm2D, Point, RGB};
+use specs::prelude::*;
+
+use crate::{Awareness, AwarenessState, Faction, FactionTable, GameLog, IsVisible, LogCategory, LogMessage, Map, MEDIUM_LIFETIME, Name, ParticleBuilder, PlayerMemory, Position, Reaction};
+
+pub const WALK_NOISE: i32 = 4;
+pub const MELEE_NOISE: i32 = 8;
+pub const EXPLOSION_NOISE: i32 = 14;
+
+pub struct NoiseRequest {
+    position: Point,
+    radius: i32,
+    source: Entity,
+}
+
+pub struct NoiseBuilder {
+    requests: Vec<NoiseRequest>
+}
+
+impl NoiseBuilder {
+    pub fn new() -> NoiseBuilder {
+        NoiseBuilder { requests: Vec::new() }
+    }
+
+    pub fn request_noise(&mut self, position: Point, radius: i32, source: Entity) {
+        self.requests.push(NoiseRequest {
+            position,
+            radius,
+            source,
+        });
+    }
+}
+
+impl Default for NoiseBuilder {
+    fn default() -> Self {
+        NoiseBuilder::new()
+    }
+}
+
+pub struct NoiseSystem;
+
+impl NoiseSystem {
+    pub const NAME: &'static str = "noise";
+}
+
+impl<'a> System<'a> for NoiseSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Entity>,
+        ReadExpect<'a, Map>,
+        WriteExpect<'a, NoiseBuilder>,
+        WriteExpect<'a, ParticleBuilder>,
+        WriteExpect<'a, GameLog>,
+        WriteStorage<'a, Awareness>,
+        WriteStorage<'a, PlayerMemory>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, IsVisible>,
+        ReadStorage<'a, Faction>,
+        ReadExpect<'a, FactionTable>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            player_entity,
+            map,
+            mut noise_builder,
+            mut particle_builder,
+            mut game_log,
+            mut awarenesses,
+            mut player_memories,
+            positions,
+            names,
+            is_visible,
+            factions,
+            faction_table,
+        ) = data;
+
+        for request in noise_builder.requests.drain(..) {
+            if request.radius <= 0 {
+                continue;
+            }
+
+            let noise_idx = map.point2d_to_index(request.position);
+            let heard_tiles = map.get_tiles_within_steps(noise_idx, request.radius);
+
+            for (entity, awareness, position) in (&entities, &mut awarenesses, &positions).join() {
+                if entity == request.source || !heard_tiles.contains(&map.xy_idx(position.x, position.y)) {
+                    continue;
+                }
+
+                if awareness.state == AwarenessState::Asleep && is_visible.get(entity).is_some() {
+                    let name = names.get(entity).map_or("Something", |name| &name.name);
+                    game_log.add(LogMessage::new(LogCategory::Status).actor(name).text(" wakes up!"));
+
+                    particle_builder.request_aura(
+                        Point::new(position.x, position.y),
+                        MEDIUM_LIFETIME,
+                        RGB::named(rltk::WHITE),
+                        rltk::to_cp437('!'),
+                    );
+                }
+
+                if awareness.state == AwarenessState::Asleep || awareness.state == AwarenessState::Wandering {
+                    awareness.state = AwarenessState::Alert;
+                }
+
+                let is_hostile_to_source = faction_table.get_entity_reaction(&factions, entity, request.source) == Reaction::Hostile;
+                let has_fresh_memory = player_memories.get(entity).is_some_and(|memory| memory.turns_since_seen == 0);
+
+                if request.source == *player_entity && is_hostile_to_source && !has_fresh_memory {
+                    player_memories
+                        .insert(entity, PlayerMemory { last_seen: request.position, turns_since_seen: 0 })
+                        .expect("Unable to insert memory");
+                }
+            }
+        }
+    }
+}
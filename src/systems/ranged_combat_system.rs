@@ -0,0 +1,144 @@
+extern crate specs;
+
+use rltk::{ColorPair, LineAlg, Point, RGB};
+use specs::prelude::*;
+
+use crate::{ActionKind, ActionRecorder, ActionResult, AttackRoll, CanShoot, CombatStats, GameEvent, IsVisible, LogCategory, LogMessage, MEDIUM_LIFETIME, Name, ParticleBuilder, Position, SHORT_LIFETIME, SuffersDamage, WantsToShoot};
+
+pub struct RangedCombatSystem;
+
+impl RangedCombatSystem {
+    pub const NAME: &'static str = "ranged_combat";
+}
+
+impl<'a> System<'a> for RangedCombatSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, WantsToShoot>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, CombatStats>,
+        WriteStorage<'a, SuffersDamage>,
+        WriteStorage<'a, CanShoot>,
+        WriteExpect<'a, ParticleBuilder>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, IsVisible>,
+        WriteExpect<'a, ActionRecorder>,
+        ReadExpect<'a, Entity>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut wants_to_shoot,
+            names,
+            combat_stats,
+            mut suffers_damage,
+            mut can_shoot,
+            mut particle_builder,
+            positions,
+            is_visible,
+            mut action_recorder,
+            player_entity,
+        ) = data;
+
+        for (entity, wants_to_shoot, name, stats, can_shoot, position) in (&entities, &wants_to_shoot, &names, &combat_stats, &mut can_shoot, &positions).join() {
+            let target_stats = combat_stats.get(wants_to_shoot.target).unwrap();
+            let target_position = positions.get(wants_to_shoot.target).unwrap();
+
+            if stats.hp <= 0 || !can_shoot.has_ammo() || target_stats.hp <= 0 {
+                action_recorder.record(entity, ActionKind::Shoot, can_shoot.time_cost, ActionResult::Failed);
+                continue;
+            }
+
+            let record = action_recorder.record(entity, ActionKind::Shoot, can_shoot.time_cost, ActionResult::Success);
+
+            if let Some(ammo) = can_shoot.ammo.as_mut() {
+                *ammo -= 1;
+            }
+
+            let start = Point::new(position.x, position.y);
+            let end = Point::new(target_position.x, target_position.y);
+
+            for tile in rltk::line2d(LineAlg::Bresenham, start, end).iter().skip(1) {
+                particle_builder.request_entity(
+                    *tile,
+                    SHORT_LIFETIME,
+                    ColorPair::new(can_shoot.projectile_fg, RGB::named(rltk::BLACK)),
+                    can_shoot.projectile_glyph,
+                );
+            }
+
+            let target_name = &names.get(wants_to_shoot.target).unwrap().name;
+            let projectile_name = &can_shoot.projectile_name;
+            let is_seen = is_visible.get(entity).is_some() || is_visible.get(wants_to_shoot.target).is_some();
+
+            let attack_roll = AttackRoll::roll(stats.power, target_stats.defense, can_shoot.damage);
+            record.event(GameEvent::Attacked { attacker: entity, target: wants_to_shoot.target, roll: attack_roll });
+
+            match attack_roll {
+                AttackRoll::Fumble => {
+                    if is_seen {
+                        record.log(LogMessage::new(LogCategory::Combat)
+                            .actor(&name.name)
+                            .text(" fumbles the ")
+                            .item(projectile_name)
+                            .text("!"));
+                    }
+                }
+                AttackRoll::Miss => {
+                    if is_seen {
+                        record.log(LogMessage::new(LogCategory::Combat)
+                            .actor(&name.name)
+                            .text("'s ")
+                            .item(projectile_name)
+                            .text(" misses ")
+                            .actor(target_name)
+                            .text("."));
+                    }
+
+                    particle_builder.request_aura(
+                        end,
+                        MEDIUM_LIFETIME,
+                        RGB::named(rltk::LIGHT_GRAY),
+                        rltk::to_cp437('○'),
+                    );
+                }
+                AttackRoll::Hit { damage } | AttackRoll::Critical { damage } => {
+                    if is_seen {
+                        let hit_verb = match attack_roll {
+                            AttackRoll::Critical { .. } => "critically hits",
+                            _ => "hits",
+                        };
+                        record.log(LogMessage::new(LogCategory::Combat)
+                            .actor(&name.name)
+                            .text("'s ")
+                            .item(projectile_name)
+                            .text(format!(" {} ", hit_verb))
+                            .actor(target_name)
+                            .text(" for ")
+                            .amount(damage)
+                            .text(" hp.")
+                            .important_if(wants_to_shoot.target == *player_entity));
+                    }
+
+                    SuffersDamage::new_damage(
+                        &mut suffers_damage,
+                        wants_to_shoot.target,
+                        damage,
+                        can_shoot.damage_type,
+                        entity);
+                }
+            }
+
+            if !can_shoot.has_ammo() && is_visible.get(entity).is_some() {
+                record.log(LogMessage::new(LogCategory::Combat)
+                    .actor(&name.name)
+                    .text(" is out of ")
+                    .item(format!("{}s", projectile_name))
+                    .text("."));
+            }
+        }
+
+        wants_to_shoot.clear();
+    }
+}
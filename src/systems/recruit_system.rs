@@ -0,0 +1,85 @@
+extern crate specs;
+
+use rltk::{Point, RGB};
+use specs::prelude::*;
+
+use crate::{ActionKind, ActionRecorder, ActionResult, Ally, Faction, LogCategory, LogMessage, MEDIUM_LIFETIME, Name, ParticleBuilder, Position, Recruitable, Renderable, WantsToRecruit};
+
+const TALK_TIME_COST: u32 = 100;
+
+pub struct RecruitSystem;
+
+impl RecruitSystem {
+    pub const NAME: &'static str = "recruit";
+}
+
+impl<'a> System<'a> for RecruitSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, WantsToRecruit>,
+        WriteStorage<'a, Recruitable>,
+        WriteStorage<'a, Ally>,
+        WriteStorage<'a, Faction>,
+        WriteStorage<'a, Renderable>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Position>,
+        WriteExpect<'a, ParticleBuilder>,
+        WriteExpect<'a, ActionRecorder>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut wants_to_recruit,
+            mut recruitables,
+            mut allies,
+            mut factions,
+            mut renderables,
+            names,
+            positions,
+            mut particle_builder,
+            mut action_recorder,
+        ) = data;
+
+        for (entity, wants_to_recruit) in (&entities, &wants_to_recruit).join() {
+            let target = wants_to_recruit.target;
+            let target_name = &names.get(target).unwrap().name;
+
+            if recruitables.remove(target).is_none() {
+                action_recorder
+                    .record(entity, ActionKind::Talk, TALK_TIME_COST, ActionResult::Failed)
+                    .log(LogMessage::new(LogCategory::Status).actor(target_name).text(" ignores you."));
+                continue;
+            }
+
+            let record = action_recorder.record(entity, ActionKind::Talk, TALK_TIME_COST, ActionResult::Success);
+
+            if let Some(faction) = factions.get(entity).cloned() {
+                factions.insert(target, faction).expect("Unable to insert faction");
+            }
+
+            allies
+                .insert(target, Ally { leader: entity })
+                .expect("Unable to insert ally");
+
+            if let Some(renderable) = renderables.get_mut(target) {
+                renderable.fg = RGB::named(rltk::CYAN);
+            }
+
+            if let Some(position) = positions.get(target) {
+                particle_builder.request_aura(
+                    Point::new(position.x, position.y),
+                    MEDIUM_LIFETIME,
+                    RGB::named(rltk::CYAN),
+                    rltk::to_cp437('♥'),
+                );
+            }
+
+            record.log(LogMessage::new(LogCategory::Status)
+                .colored(target_name, RGB::named(rltk::CYAN))
+                .text(" agrees to follow you."));
+        }
+
+        wants_to_recruit.clear();
+    }
+}
@@ -0,0 +1,55 @@
+extern crate specs;
+
+use specs::prelude::*;
+
+use crate::{InBackpack, Map, Position, SpeedModifiers, SpeedSource, TakesTurn, TileType};
+
+const MUD_MULTIPLIER: f32 = 2.;
+const ENCUMBRANCE_LIMIT: usize = 6;
+const ENCUMBRANCE_MULTIPLIER: f32 = 1.5;
+
+pub struct SpeedSystem;
+
+impl SpeedSystem {
+    pub const NAME: &'static str = "speed";
+}
+
+impl<'a> System<'a> for SpeedSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Map>,
+        ReadStorage<'a, TakesTurn>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, InBackpack>,
+        WriteStorage<'a, SpeedModifiers>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            map,
+            takes_turn,
+            positions,
+            in_backpacks,
+            mut speed_modifiers,
+        ) = data;
+
+        for (entity, _takes_turn, position, speed_modifiers) in (&entities, &takes_turn, &positions, &mut speed_modifiers).join() {
+            match map.get(position.x, position.y) {
+                TileType::Mud => speed_modifiers.set(SpeedSource::Terrain, MUD_MULTIPLIER, None),
+                _ => speed_modifiers.clear(SpeedSource::Terrain),
+            }
+
+            let carried_items = in_backpacks
+                .join()
+                .filter(|in_backpack| in_backpack.owner == entity)
+                .count();
+
+            if carried_items > ENCUMBRANCE_LIMIT {
+                speed_modifiers.set(SpeedSource::Encumbrance, ENCUMBRANCE_MULTIPLIER, None);
+            } else {
+                speed_modifiers.clear(SpeedSource::Encumbrance);
+            }
+        }
+    }
+}
@@ -0,0 +1,88 @@
+extern crate specs;
+
+use specs::prelude::*;
+
+use crate::{GameEvent, GameEvents, Statistics};
+
+pub struct StatisticsSystem;
+
+impl StatisticsSystem {
+    pub const NAME: &'static str = "statistics";
+}
+
+impl<'a> System<'a> for StatisticsSystem {
+    type SystemData = (
+        ReadExpect<'a, GameEvents>,
+        WriteStorage<'a, Statistics>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            game_events,
+            mut statistics,
+        ) = data;
+
+        for event in game_events.iter() {
+            match *event {
+                GameEvent::Moved { entity, .. } => {
+                    if let Some(stats) = statistics.get_mut(entity) {
+                        stats.steps += 1;
+                    }
+                }
+                GameEvent::Damaged { victim, source, amount, .. } => {
+                    if let Some(stats) = statistics.get_mut(source) {
+                        stats.damage_dealt += amount;
+                    }
+
+                    if let Some(stats) = statistics.get_mut(victim) {
+                        stats.damage_taken += amount;
+                    }
+                }
+                GameEvent::Died { killer, .. } => {
+                    if let Some(stats) = statistics.get_mut(killer) {
+                        stats.kills += 1;
+                    }
+                }
+                GameEvent::ItemUsed { user, .. } => {
+                    if let Some(stats) = statistics.get_mut(user) {
+                        stats.items_used += 1;
+                    }
+                }
+                GameEvent::ActionTaken { .. } | GameEvent::Attacked { .. } | GameEvent::StatusApplied { .. } => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use specs::prelude::*;
+
+    use crate::{DamageType, GameEvent, GameEvents, Statistics, StatisticsSystem};
+
+    #[test]
+    fn counts_damage_and_kills_from_events() {
+        let mut world = World::new();
+        world.register::<Statistics>();
+        world.insert(GameEvents::new());
+
+        let player = world.create_entity().with(Statistics::default()).build();
+        let goblin = world.create_entity().with(Statistics::default()).build();
+
+        {
+            let mut game_events = world.write_resource::<GameEvents>();
+            game_events.push(GameEvent::Damaged { victim: player, source: goblin, amount: 3, damage_type: DamageType::Physical });
+            game_events.push(GameEvent::Damaged { victim: goblin, source: player, amount: 5, damage_type: DamageType::Fire });
+            game_events.push(GameEvent::Died { victim: goblin, killer: player });
+        }
+
+        StatisticsSystem.run_now(&world);
+
+        let statistics = world.read_storage::<Statistics>();
+        let player_statistics = statistics.get(player).unwrap();
+        assert_eq!(player_statistics.kills, 1);
+        assert_eq!(player_statistics.damage_dealt, 5);
+        assert_eq!(player_statistics.damage_taken, 3);
+        assert_eq!(statistics.get(goblin).unwrap().damage_dealt, 3);
+    }
+}
@@ -3,7 +3,7 @@ extern crate specs;
 use rltk::Point;
 use specs::prelude::*;
 
-use crate::{GlobalTurnTimeScore, MEDIUM_LIFETIME, ParticleBuilder, Position, TakesTurn, WaitCause, WantsToWait};
+use crate::{ActionKind, ActionRecorder, ActionResult, GlobalTurnTimeScore, MEDIUM_LIFETIME, ParticleBuilder, Position, TakesTurn, WaitCause, WantsToWait};
 
 pub struct WaitSystem;
 
@@ -11,26 +11,29 @@ impl<'a> System<'a> for WaitSystem {
     type SystemData = (
         Entities<'a>,
         WriteStorage<'a, WantsToWait>,
-        WriteStorage<'a, TakesTurn>,
+        ReadStorage<'a, TakesTurn>,
         ReadExpect<'a, GlobalTurnTimeScore>,
         WriteExpect<'a, ParticleBuilder>,
         ReadStorage<'a, Position>,
+        WriteExpect<'a, ActionRecorder>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
         let (
             entities,
             mut wants_to_wait,
-            mut takes_turn,
+            takes_turn,
             global_turn_time_score,
             mut particle_builder,
             positions,
+            mut action_recorder,
         ) = data;
 
         let target_time_score = global_turn_time_score.time_score + 1;
 
-        for (entity, wants_to_wait, mut takes_turn) in (&entities, &wants_to_wait, &mut takes_turn).join() {
-            takes_turn.time_score = target_time_score;
+        for (entity, wants_to_wait, takes_turn) in (&entities, &wants_to_wait, &takes_turn).join() {
+            let wait_time = target_time_score.saturating_sub(takes_turn.time_score);
+            action_recorder.record(entity, ActionKind::Wait, wait_time, ActionResult::Success);
 
             if let Some(position) = positions.get(entity) {
                 match wants_to_wait.cause {
@@ -50,6 +53,14 @@ impl<'a> System<'a> for WaitSystem {
                             rltk::to_cp437('♪'),
                         );
                     }
+                    WaitCause::Sleep => {
+                        particle_builder.request_aura(
+                            Point::new(position.x, position.y),
+                            MEDIUM_LIFETIME,
+                            rltk::RGB::named(rltk::LIGHT_BLUE),
+                            rltk::to_cp437('z'),
+                        );
+                    }
                     WaitCause::Stun => {}
                 }
             }